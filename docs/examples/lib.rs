@@ -169,7 +169,7 @@ pub fn get_unpan_xing_index(current: &JsValue, lichun: &JsValue) -> JsValue {
 }
 
 #[wasm_bindgen]
-pub fn get_xiaguatu_from_unpan_index(params: &JsValue) -> JsValue {
+pub fn get_xiaguatu_from_unpan_index(params: &JsValue) -> Result<JsValue, JsValue> {
     let params: XiaGuaTuParams = params.into_serde().unwrap();
 
     let unpan_xing_order: [usize; 9] =
@@ -185,9 +185,10 @@ pub fn get_xiaguatu_from_unpan_index(params: &JsValue) -> JsValue {
         &unpan_xing_order,
         params.xiang_xing_direction.as_str(),
         params.xiang_xing_sector,
-    );
+    )
+    .map_err(|err| JsValue::from(err.to_string()))?;
 
-    JsValue::from_serde(&xia_gua_tu).unwrap()
+    Ok(JsValue::from_serde(&xia_gua_tu).unwrap())
 }
 
 // A simple accessor for getting values in JIU_XING_DI_PAN_POSITIONS.
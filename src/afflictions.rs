@@ -0,0 +1,354 @@
+//! Every year, there are directions which should not
+//! be disturbed (by construction, renovation, or
+//! digging). Of them, the most notable are:
+//!
+//! (1) 太歲 (Tai-Sui), or "the Grand Duke" --> The
+//! mountain of the year branch.
+//! (2) 歲破 (Sui-Po), or "the Year Breaker" --> The
+//! mountain opposite to 太歲 (Tai-Sui).
+//! (3) 三煞 (San-Sha), or "the Three Killings" --> The
+//! direction opposite to the cardinal direction of
+//! 三合 (San-He) which the year branch belongs to.
+//! For 申子辰 (water), it is the south. For 寅午戌
+//! (fire), the north. For 巳酉丑 (metal), the east.
+//! For 亥卯未 (wood), the west.
+//! (4) 五黃 (Wu-Huang), or "the 5 Yellow" --> The
+//! direction where 五黄土星 (5 Yellow) comes to in
+//! 年紫白 (Nian Zi-Bai).
+//!
+//! 太歲 (Tai-Sui) and 歲破 (Sui-Po) are spans of a
+//! mountain (15 degrees), whereas 三煞 (San-Sha) and
+//! 五黃 (Wu-Huang) are spans of a direction (45
+//! degrees).
+
+use chrono::naive::NaiveDate;
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+use crate::compass::{
+    DIRECTIONS, TWENTYFOUR_INDEX_TO_DIRECTIONS,
+    TWENTYFOUR_ORDER_START_NORTH,
+};
+use crate::ephemeris::SunLongitudeProvider;
+use crate::jiuxing::{
+    annual_star, fly_stars, Flight,
+};
+use crate::solar_terms::{
+    get_instant_of_sun_longitude,
+    get_instant_of_sun_longitude_with,
+    year_from_lichun,
+};
+
+/// Kinds of annual afflictions.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum AfflictionKind {
+    TaiSui,  // 太歲
+    SuiPo,   // 歲破
+    SanSha,  // 三煞
+    WuHuang, // 五黃
+}
+
+/// Whether the affliction spans a mountain (15
+/// degrees) or a direction (45 degrees).
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum SpanKind {
+    Mountain,
+    Direction,
+}
+
+/// A struct representing an annual affliction.
+/// `direction` is the direction which the affliction
+/// is in, and `mountains` are indexes for
+/// `TWENTYFOUR_ORDER_START_NORTH` (in clockwise order).
+/// `start` and `end` are the degrees (clockwise from
+/// the north) of the span. For spans across the north,
+/// `start` is greater than `end` (e.g. 337.5 to 22.5).
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize,
+)]
+pub struct Affliction {
+    pub kind: AfflictionKind,
+    pub span: SpanKind,
+    pub direction: String,
+    pub mountains: Vec<usize>,
+    pub start: f32,
+    pub end: f32,
+}
+
+/// A struct representing annual afflictions for the
+/// year. `branch` is the year branch (0 for 子, 1 for
+/// 丑, ...). `afflictions` has no 五黃 (Wu-Huang) when
+/// 五黄土星 (5 Yellow) is in the center.
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize,
+)]
+pub struct AnnualAfflictions {
+    pub year: i32,
+    pub branch: usize,
+    pub afflictions: Vec<Affliction>,
+}
+
+impl AnnualAfflictions {
+    /// Returns `Affliction` of the given kind.
+    pub fn get(
+        &self,
+        kind: AfflictionKind,
+    ) -> Option<&Affliction> {
+        self.afflictions.iter().find(|affliction| {
+            affliction.kind == kind
+        })
+    }
+}
+
+/// The index of the mountain for the given branch.
+fn branch_mountain(branch: usize) -> usize {
+    // Branches always exist in the table.
+    TWENTYFOUR_ORDER_START_NORTH
+        .iter()
+        .position(|&t| t == (2, branch))
+        .unwrap()
+}
+
+/// `Affliction` for a span of the mountain.
+fn mountain_span(
+    kind: AfflictionKind,
+    mountain: usize,
+) -> Affliction {
+    let start: f32 = (mountain as f32 * 15.0 - 7.5)
+        .rem_euclid(360.0);
+    Affliction {
+        kind,
+        span: SpanKind::Mountain,
+        direction: TWENTYFOUR_INDEX_TO_DIRECTIONS
+            [mountain]
+            .direction
+            .clone(),
+        mountains: vec![mountain],
+        start,
+        end: (start + 15.0).rem_euclid(360.0),
+    }
+}
+
+/// `Affliction` for a span of the direction.
+fn direction_span(
+    kind: AfflictionKind,
+    dir: &str,
+) -> Affliction {
+    // The first mountain (sector 1) of the direction.
+    // Those for the north begin from [23].
+    let first: usize = TWENTYFOUR_INDEX_TO_DIRECTIONS
+        .iter()
+        .position(|d| {
+            d.direction == dir && d.sector == 1
+        })
+        .unwrap();
+    let mountains: Vec<usize> =
+        (0..3).map(|i| (first + i) % 24).collect();
+    let start: f32 =
+        (first as f32 * 15.0 - 7.5).rem_euclid(360.0);
+    Affliction {
+        kind,
+        span: SpanKind::Direction,
+        direction: dir.to_string(),
+        mountains,
+        start,
+        end: (start + 45.0).rem_euclid(360.0),
+    }
+}
+
+/// Returns `AnnualAfflictions` for the given year
+/// (which begins from 立春 (Li-Chun)).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::afflictions::{annual_afflictions, AfflictionKind};
+///
+/// // 2024 (甲辰)
+/// let afflictions = annual_afflictions(2024);
+///
+/// let tai_sui = afflictions.get(AfflictionKind::TaiSui).unwrap();
+/// assert_eq!(tai_sui.direction, "se");
+/// assert_eq!((tai_sui.start, tai_sui.end), (112.5, 127.5));
+///
+/// let san_sha = afflictions.get(AfflictionKind::SanSha).unwrap();
+/// assert_eq!(san_sha.direction, "s");
+/// assert_eq!((san_sha.start, san_sha.end), (157.5, 202.5));
+///
+/// let wu_huang = afflictions.get(AfflictionKind::WuHuang).unwrap();
+/// assert_eq!(wu_huang.direction, "w");
+/// ```
+pub fn annual_afflictions(
+    year: i32,
+) -> AnnualAfflictions {
+    // 0   1   2   3   4   5   6   7   8  9   10  11
+    // 子  丑  寅  卯  辰  巳  午  未  申  酉  戌  亥
+    let branch: usize =
+        (year + 8).rem_euclid(12) as usize;
+    let tai_sui: usize = branch_mountain(branch);
+
+    let san_sha: &str = match branch % 4 {
+        0 => "s", // 申子辰
+        1 => "e", // 巳酉丑
+        2 => "n", // 寅午戌
+        _ => "w", // 亥卯未
+    };
+
+    let mut afflictions: Vec<Affliction> = vec![
+        mountain_span(
+            AfflictionKind::TaiSui,
+            tai_sui,
+        ),
+        mountain_span(
+            AfflictionKind::SuiPo,
+            (tai_sui + 12) % 24,
+        ),
+        direction_span(
+            AfflictionKind::SanSha,
+            san_sha,
+        ),
+    ];
+
+    // Every 九星 (Jiu-Xing) index is valid.
+    let grid = fly_stars(
+        annual_star(year),
+        "n",
+        Flight::Forward,
+    )
+    .unwrap();
+    if let Some(dir) = DIRECTIONS
+        .iter()
+        .find(|dir| grid.get(dir) == Some(4))
+    {
+        afflictions.push(direction_span(
+            AfflictionKind::WuHuang,
+            dir,
+        ));
+    }
+
+    AnnualAfflictions {
+        year,
+        branch,
+        afflictions,
+    }
+}
+
+/// The same as `annual_afflictions`, but takes a date,
+/// and finds 立春 (Li-Chun) for the year by itself.
+pub fn get_annual_afflictions(
+    current: NaiveDate,
+) -> AnnualAfflictions {
+    annual_afflictions(year_from_lichun(
+        current.and_hms(0, 0, 0),
+        get_instant_of_sun_longitude(
+            current.year(),
+            315.0,
+        ),
+    ))
+}
+
+/// The same as `get_annual_afflictions`, but
+/// calculates the position of the sun using the given
+/// provider.
+pub fn get_annual_afflictions_with(
+    provider: &dyn SunLongitudeProvider,
+    current: NaiveDate,
+) -> AnnualAfflictions {
+    annual_afflictions(year_from_lichun(
+        current.and_hms(0, 0, 0),
+        get_instant_of_sun_longitude_with(
+            provider,
+            current.year(),
+            315.0,
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annual_afflictions() {
+        // 2024 (甲辰)
+        let afflictions = annual_afflictions(2024);
+        assert_eq!(afflictions.branch, 4);
+        assert_eq!(afflictions.afflictions.len(), 4);
+
+        let sui_po = afflictions
+            .get(AfflictionKind::SuiPo)
+            .unwrap();
+        assert_eq!(sui_po.span, SpanKind::Mountain);
+        assert_eq!(sui_po.direction, "nw");
+        assert_eq!(sui_po.mountains, vec![20]);
+        assert_eq!(
+            (sui_po.start, sui_po.end),
+            (292.5, 307.5)
+        );
+
+        let wu_huang = afflictions
+            .get(AfflictionKind::WuHuang)
+            .unwrap();
+        assert_eq!(
+            wu_huang.span,
+            SpanKind::Direction
+        );
+        assert_eq!(
+            wu_huang.mountains,
+            vec![17, 18, 19]
+        );
+        assert_eq!(
+            (wu_huang.start, wu_huang.end),
+            (247.5, 292.5)
+        );
+    }
+
+    #[test]
+    fn test_annual_afflictions_across_north() {
+        // 2026 (丙午) has 三煞 (San-Sha) in the north,
+        // and 2020 (庚子) has 太歲 (Tai-Sui) in 子.
+        let san_sha = annual_afflictions(2026)
+            .get(AfflictionKind::SanSha)
+            .cloned()
+            .unwrap();
+        assert_eq!(san_sha.mountains, vec![23, 0, 1]);
+        assert_eq!(
+            (san_sha.start, san_sha.end),
+            (337.5, 22.5)
+        );
+
+        let tai_sui = annual_afflictions(2020)
+            .get(AfflictionKind::TaiSui)
+            .cloned()
+            .unwrap();
+        assert_eq!(tai_sui.direction, "n");
+        assert_eq!(
+            (tai_sui.start, tai_sui.end),
+            (352.5, 7.5)
+        );
+    }
+
+    #[test]
+    fn test_annual_afflictions_without_wu_huang() {
+        // 五黄土星 (5 Yellow) is in the center for 2031.
+        let afflictions = annual_afflictions(2031);
+        assert!(afflictions
+            .get(AfflictionKind::WuHuang)
+            .is_none());
+    }
+}
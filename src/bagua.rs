@@ -278,6 +278,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_constant_bagua_directions() {
+        // 坎 n, 坤 sw, 震 e, 巽 se, 乾 nw, 兌 w, 艮 ne,
+        // 離 s
+        let expected = [
+            (1, "n"),
+            (2, "sw"),
+            (3, "e"),
+            (4, "se"),
+            (6, "nw"),
+            (7, "w"),
+            (8, "ne"),
+            (9, "s"),
+        ];
+        assert_eq!(BAGUA_LO_SHU_ORDER.len(), 8);
+        for (gua, (num, dir)) in
+            BAGUA_LO_SHU_ORDER.iter().zip(expected)
+        {
+            assert_eq!(gua.jiuxing_num, num);
+            assert_eq!(gua.direction, dir);
+        }
+    }
+
     // TODO: BAGUA_LO_SHU_COMPASS_ORDER_INDEXES
     // TODO: BAGUA_LO_SHU_COMPASS_ORDER
     // TODO: gua_for_start_north
@@ -0,0 +1,728 @@
+//! 八宅 (Ba-Zhai), or "Eight Mansions", is a school
+//! of Feng-Shui which tells good and bad directions
+//! for a person. For each person, there is 本命卦
+//! (Ben-Ming Gua), or "Kua number", which is found
+//! from the year of birth and gender. For men, it is
+//! the same as 年紫白 (Nian Zi-Bai) for the year
+//! (which begins from 立春 (Li-Chun)). For women, it
+//! is the number which makes 15 when added to that
+//! of men. Since there is no 卦 (Gua) for 5, it is
+//! replaced with 2 for men, and with 8 for women.
+//!
+//! Depending on the Kua number, 8 directions are
+//! given 8 stars (4 auspicious and 4 inauspicious):
+//!
+//! [0] 生氣 (Sheng-Qi) &dash;&dash;&gt; Vitality
+//! [1] 天醫 (Tian-Yi) &dash;&dash;&gt; Health
+//! [2] 延年 (Yan-Nian) &dash;&dash;&gt; Longevity
+//! [3] 伏位 (Fu-Wei) &dash;&dash;&gt; Stability
+//! [4] 禍害 (Huo-Hai) &dash;&dash;&gt; Mishaps
+//! [5] 六煞 (Liu-Sha) &dash;&dash;&gt; Six Killings
+//! [6] 五鬼 (Wu-Gui) &dash;&dash;&gt; Five Ghosts
+//! [7] 絕命 (Jue-Ming) &dash;&dash;&gt; Total Loss
+//!
+//! Also, people with 1, 3, 4, or 9 belong to 東四命
+//! (Dong-Si Ming), or "East group", and people with 2,
+//! 6, 7, or 8 belong to 西四命 (Xi-Si Ming), or "West
+//! group".
+//!
+//! Likewise, a building has its own 卦 (Gua), or 宅卦
+//! (Zhai-Gua), which is 卦 (Gua) of the sitting
+//! direction. 8 stars (called 遊年 (You-Nian)) are
+//! distributed around the building just like they are
+//! for a person with the same number, and placing a
+//! room in the direction of auspicious stars for the
+//! occupant is considered good.
+
+use chrono::naive::NaiveDateTime;
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+use crate::bagua::{Gua, BAGUA_LO_SHU_ORDER};
+use crate::compass::{
+    get_opposite_direction,
+    get_twentyfour_direction_from_degrees, Direction,
+    DIRECTION_POSITIONS_IN_CHART,
+};
+use crate::ephemeris::{
+    SunLongitudeProvider, DEFAULT_PROVIDER,
+};
+use crate::jiuxing::{annual_star, JiuXingError};
+use crate::language::{
+    Language, LanguageData, LanguageTrait,
+    NameDataTrait,
+};
+use crate::solar_terms::{
+    get_instant_of_sun_longitude_with,
+    year_from_lichun,
+};
+use crate::utils::get_json;
+
+/// Gender of the person.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum Gender {
+    Male,
+    Female,
+}
+
+/// Kinds of stars for 八宅 (Ba-Zhai). The order is
+/// the same as that of `BA_ZHAI_STARS`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum BaZhaiStarKind {
+    ShengQi, // 生氣
+    TianYi,  // 天醫
+    YanNian, // 延年
+    FuWei,   // 伏位
+    HuoHai,  // 禍害
+    LiuSha,  // 六煞
+    WuGui,   // 五鬼
+    JueMing, // 絕命
+}
+
+/// East group (東四命) or West group (西四命). The
+/// order is the same as that of `BA_ZHAI_GROUPS`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum BaZhaiGroup {
+    East, // 東四命
+    West, // 西四命
+}
+
+/// A struct representing a star for 八宅 (Ba-Zhai).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaZhaiStar {
+    pub id: u8,
+    pub name: Language,
+    pub auspicious: bool,
+}
+
+/// A temporary struct for loading JSON data when
+/// defining a static const `BA_ZHAI_STARS`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaZhaiStarRawData {
+    pub id: u8,
+    pub name: LanguageData,
+    pub auspicious: bool,
+}
+
+/// A struct representing a name of the group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaZhaiGroupName {
+    pub id: u8,
+    pub name: Language,
+}
+
+/// A temporary struct for loading JSON data when
+/// defining a static const `BA_ZHAI_GROUPS`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaZhaiGroupRawData {
+    pub id: u8,
+    pub name: LanguageData,
+}
+
+/// A struct representing the star for a direction.
+/// `direction` is one of `DIRECTIONS`, and `gua` is
+/// 卦 (Gua) for the direction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KuaDirection {
+    pub kind: BaZhaiStarKind,
+    pub name: Language,
+    pub auspicious: bool,
+    pub direction: String,
+    pub gua: Gua,
+}
+
+/// A struct representing 本命卦 (Ben-Ming Gua).
+/// `num` is the Kua number (from 1 to 9 except for
+/// 5), and `directions` are sorted in the order of
+/// `BA_ZHAI_STARS`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Kua {
+    pub num: usize,
+    pub gua: Gua,
+    pub group: BaZhaiGroup,
+    pub group_name: Language,
+    pub directions: Vec<KuaDirection>,
+}
+
+impl LanguageTrait for BaZhaiStar {
+    fn name(&self) -> Box<Language> {
+        Box::new(self.name.clone())
+    }
+}
+
+impl NameDataTrait for BaZhaiStarRawData {
+    fn name(&self) -> Box<LanguageData> {
+        Box::new(self.name.clone())
+    }
+}
+
+impl LanguageTrait for BaZhaiGroupName {
+    fn name(&self) -> Box<Language> {
+        Box::new(self.name.clone())
+    }
+}
+
+impl NameDataTrait for BaZhaiGroupRawData {
+    fn name(&self) -> Box<LanguageData> {
+        Box::new(self.name.clone())
+    }
+}
+
+impl LanguageTrait for KuaDirection {
+    fn name(&self) -> Box<Language> {
+        Box::new(self.name.clone())
+    }
+}
+
+lazy_static! {
+    /// A static vector with 8 items, each represents
+    /// a star for 八宅 (Ba-Zhai).
+    ///
+    /// For attributes details stored in the vector is
+    /// found in JSON file:
+    /// `src/json/bazhai.json`
+    pub static ref BA_ZHAI_STARS: Vec<BaZhaiStar> = {
+        let json = &include_str!("../json/bazhai.json");
+        let data: Vec<BaZhaiStarRawData> =
+            get_json::<BaZhaiStarRawData>(json);
+        data.iter()
+            .map(|item| BaZhaiStar {
+                id: item.id,
+                name: item.language_from_data(),
+                auspicious: item.auspicious,
+            })
+            .collect()
+    };
+
+    /// A static vector with 2 items, East group
+    /// (東四命) and West group (西四命).
+    ///
+    /// For attributes details stored in the vector is
+    /// found in JSON file:
+    /// `src/json/bazhai_groups.json`
+    pub static ref BA_ZHAI_GROUPS: Vec<BaZhaiGroupName> = {
+        let json = &include_str!("../json/bazhai_groups.json");
+        let data: Vec<BaZhaiGroupRawData> =
+            get_json::<BaZhaiGroupRawData>(json);
+        data.iter()
+            .map(|item| BaZhaiGroupName {
+                id: item.id,
+                name: item.language_from_data(),
+            })
+            .collect()
+    };
+}
+
+/// For each Kua number, directions for 生氣
+/// (Sheng-Qi), 天醫 (Tian-Yi), 延年 (Yan-Nian), 伏位
+/// (Fu-Wei), 禍害 (Huo-Hai), 六煞 (Liu-Sha), 五鬼
+/// (Wu-Gui), and 絕命 (Jue-Ming).
+pub const KUA_DIRECTIONS: [(usize, [&str; 8]); 8] = [
+    (1, ["se", "e", "s", "n", "w", "nw", "ne", "sw"]),
+    (2, ["ne", "w", "nw", "sw", "e", "s", "se", "n"]),
+    (3, ["s", "n", "se", "e", "sw", "ne", "nw", "w"]),
+    (4, ["n", "s", "e", "se", "nw", "w", "sw", "ne"]),
+    (6, ["w", "ne", "sw", "nw", "se", "n", "e", "s"]),
+    (7, ["nw", "sw", "ne", "w", "n", "se", "s", "e"]),
+    (8, ["sw", "nw", "w", "ne", "s", "e", "n", "se"]),
+    (9, ["e", "se", "n", "s", "ne", "sw", "w", "nw"]),
+];
+
+const BA_ZHAI_STAR_KINDS: [BaZhaiStarKind; 8] = [
+    BaZhaiStarKind::ShengQi,
+    BaZhaiStarKind::TianYi,
+    BaZhaiStarKind::YanNian,
+    BaZhaiStarKind::FuWei,
+    BaZhaiStarKind::HuoHai,
+    BaZhaiStarKind::LiuSha,
+    BaZhaiStarKind::WuGui,
+    BaZhaiStarKind::JueMing,
+];
+
+/// Returns the Kua number for the given year (which
+/// begins from 立春 (Li-Chun)) and gender.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::bazhai::{kua_number, Gender};
+///
+/// assert_eq!(kua_number(1984, Gender::Male), 7);
+/// assert_eq!(kua_number(1984, Gender::Female), 8);
+/// // 5 is replaced with 2 for men.
+/// assert_eq!(kua_number(1986, Gender::Male), 2);
+/// ```
+pub fn kua_number(
+    year: i32,
+    gender: Gender,
+) -> usize {
+    let male: usize = annual_star(year) + 1;
+    match gender {
+        Gender::Male => match male {
+            5 => 2,
+            num => num,
+        },
+        Gender::Female => match (14 - male) % 9 + 1 {
+            5 => 8,
+            num => num,
+        },
+    }
+}
+
+/// Returns `Kua` for the given Kua number. Returns
+/// `None` for 5 or numbers out of range.
+pub fn kua_from_number(num: usize) -> Option<Kua> {
+    let (_, dirs) = KUA_DIRECTIONS
+        .iter()
+        .find(|(kua, _)| *kua == num)?;
+
+    let gua_for = |dir: &str| -> Option<Gua> {
+        BAGUA_LO_SHU_ORDER
+            .iter()
+            .find(|gua| gua.direction == dir)
+            .cloned()
+    };
+
+    let directions: Vec<KuaDirection> = dirs
+        .iter()
+        .zip(BA_ZHAI_STARS.iter())
+        .enumerate()
+        .map(|(i, (dir, star))| {
+            Some(KuaDirection {
+                kind: BA_ZHAI_STAR_KINDS[i],
+                name: star.name.clone(),
+                auspicious: star.auspicious,
+                direction: dir.to_string(),
+                gua: gua_for(dir)?,
+            })
+        })
+        .collect::<Option<Vec<KuaDirection>>>()?;
+
+    let group: BaZhaiGroup = match num {
+        1 | 3 | 4 | 9 => BaZhaiGroup::East,
+        _ => BaZhaiGroup::West,
+    };
+
+    Some(Kua {
+        num,
+        gua: BAGUA_LO_SHU_ORDER
+            .iter()
+            .find(|gua| {
+                gua.jiuxing_num as usize == num
+            })?
+            .clone(),
+        group,
+        group_name: BA_ZHAI_GROUPS[group as usize]
+            .name
+            .clone(),
+        directions,
+    })
+}
+
+/// Returns `Kua` for the given birth date and time
+/// (UT) and gender. When born before the instant of
+/// 立春 (Li-Chun), it belongs to the previous year.
+///
+/// Example:
+/// ```rust
+/// use chrono::NaiveDate;
+/// use mikaboshi::bazhai::{get_kua, BaZhaiGroup, Gender, Kua};
+///
+/// let birth = NaiveDate::from_ymd(1984, 3, 1).and_hms(12, 0, 0);
+/// let kua: Kua = get_kua(birth, Gender::Male);
+/// assert_eq!(kua.num, 7);
+/// assert_eq!(kua.group, BaZhaiGroup::West);
+/// assert_eq!(kua.directions[0].direction, "nw");
+/// ```
+pub fn get_kua(
+    birth: NaiveDateTime,
+    gender: Gender,
+) -> Kua {
+    get_kua_with(&DEFAULT_PROVIDER, birth, gender)
+}
+
+/// The same as `get_kua`, but calculates the position
+/// of the sun using the given provider.
+pub fn get_kua_with(
+    provider: &dyn SunLongitudeProvider,
+    birth: NaiveDateTime,
+    gender: Gender,
+) -> Kua {
+    let year: i32 = year_from_lichun(
+        birth,
+        get_instant_of_sun_longitude_with(
+            provider,
+            birth.year(),
+            315.0,
+        ),
+    );
+    // `kua_number` never returns 5.
+    kua_from_number(kua_number(year, gender)).unwrap()
+}
+
+/// A struct representing a palace for `HouseChart`.
+/// `direction` is that of the palace ("" being the
+/// center), `star` is 遊年 (You-Nian) of the building,
+/// and `occupant` is that of the occupant (only when
+/// overlaid with `HouseChart::overlay`). There are no
+/// stars in the center.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HousePalace {
+    pub direction: String,
+    pub star: Option<KuaDirection>,
+    pub occupant: Option<KuaDirection>,
+}
+
+impl HousePalace {
+    /// Whether the palace is compatible with the
+    /// occupant (when the star for the occupant is
+    /// auspicious). Returns `None` for the center, or
+    /// when not overlaid.
+    pub fn is_compatible(&self) -> Option<bool> {
+        self.occupant.as_ref().map(|s| s.auspicious)
+    }
+}
+
+/// A struct representing the chart for a building.
+/// `kua` is 宅卦 (Zhai-Gua) of the building, and
+/// `palaces` are laid out in the same manner as
+/// `DIRECTION_POSITIONS_IN_CHART` for `direction`
+/// (which is the direction the device is pointing).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HouseChart {
+    pub kua: Kua,
+    pub sitting: Direction,
+    pub facing: String,
+    pub direction: String,
+    pub palaces: Vec<HousePalace>,
+}
+
+impl HouseChart {
+    /// Returns `HousePalace` for the given compass
+    /// direction ("" being the center).
+    pub fn get(
+        &self,
+        direction: &str,
+    ) -> Option<&HousePalace> {
+        self.palaces
+            .iter()
+            .find(|p| p.direction == direction)
+    }
+
+    /// Whether the occupant belongs to the same group
+    /// (東四命 or 西四命) as the building.
+    pub fn matches_group(&self, kua: &Kua) -> bool {
+        self.kua.group == kua.group
+    }
+
+    /// Overlays the stars for the occupant over the
+    /// chart.
+    ///
+    /// Example:
+    /// ```rust
+    /// use mikaboshi::bazhai::{get_house_chart, kua_from_number};
+    ///
+    /// // 坎宅 (Kan-Zhai) sitting north.
+    /// let chart = get_house_chart(0.0, "n").unwrap();
+    /// // A person with 9 (離).
+    /// let kua = kua_from_number(9).unwrap();
+    /// let chart = chart.overlay(&kua);
+    /// assert!(chart.matches_group(&kua));
+    /// assert_eq!(chart.get("n").unwrap().is_compatible(), Some(true));
+    /// assert_eq!(chart.get("w").unwrap().is_compatible(), Some(false));
+    /// ```
+    pub fn overlay(&self, kua: &Kua) -> HouseChart {
+        let mut chart: HouseChart = self.clone();
+        for palace in chart.palaces.iter_mut() {
+            palace.occupant = kua
+                .directions
+                .iter()
+                .find(|d| {
+                    d.direction == palace.direction
+                })
+                .cloned();
+        }
+        chart
+    }
+}
+
+/// Returns `HouseChart` for the given sitting bearing
+/// (in degrees) laid out for `layout_direction` (which
+/// is the direction the device is pointing).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::bazhai::{get_house_chart, BaZhaiStarKind, HouseChart};
+///
+/// // 坤宅 (Kun-Zhai) sitting south-west.
+/// let chart: HouseChart = get_house_chart(225.0, "n").unwrap();
+/// assert_eq!(chart.kua.num, 2);
+/// assert_eq!(chart.facing, "ne");
+/// assert_eq!(
+///     chart.get("ne").unwrap().star.as_ref().unwrap().kind,
+///     BaZhaiStarKind::ShengQi
+/// );
+/// ```
+pub fn get_house_chart(
+    sitting_degrees: f32,
+    layout_direction: &str,
+) -> Result<HouseChart, JiuXingError> {
+    if !sitting_degrees.is_finite() {
+        return Err(JiuXingError::InvalidDegrees(
+            sitting_degrees,
+        ));
+    }
+    let positions: &[&str; 9] =
+        DIRECTION_POSITIONS_IN_CHART
+            .get(layout_direction)
+            .ok_or_else(|| {
+                JiuXingError::InvalidDirection(
+                    layout_direction.to_string(),
+                )
+            })?;
+
+    let sitting: Direction =
+        get_twentyfour_direction_from_degrees(
+            sitting_degrees.rem_euclid(360.0),
+        );
+
+    // 宅卦 (Zhai-Gua) is 卦 (Gua) of the sitting.
+    let num: usize = BAGUA_LO_SHU_ORDER
+        .iter()
+        .find(|gua| {
+            gua.direction == sitting.direction
+        })
+        .map(|gua| gua.jiuxing_num as usize)
+        .ok_or_else(|| {
+            JiuXingError::InvalidDirection(
+                sitting.direction.clone(),
+            )
+        })?;
+    // 卦 (Gua) of a direction is never 5 (the center).
+    let kua: Kua = kua_from_number(num).unwrap();
+
+    let palaces: Vec<HousePalace> = positions
+        .iter()
+        .map(|dir| HousePalace {
+            direction: dir.to_string(),
+            star: kua
+                .directions
+                .iter()
+                .find(|d| d.direction == *dir)
+                .cloned(),
+            occupant: None,
+        })
+        .collect();
+
+    Ok(HouseChart {
+        facing: get_opposite_direction(
+            &sitting.direction,
+        )
+        .to_string(),
+        kua,
+        sitting,
+        direction: layout_direction.to_string(),
+        palaces,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compass::DIRECTIONS;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_constant_ba_zhai_stars() {
+        assert_eq!(BA_ZHAI_STARS.len(), 8);
+        assert_eq!(
+            BA_ZHAI_STARS
+                [BaZhaiStarKind::JueMing as usize]
+                .alphabet(),
+            "絕命"
+        );
+        assert_eq!(
+            BA_ZHAI_GROUPS
+                [BaZhaiGroup::East as usize]
+                .alphabet(),
+            "東四命"
+        );
+    }
+
+    #[test]
+    fn test_kua_number() {
+        assert_eq!(kua_number(2024, Gender::Male), 3);
+        assert_eq!(
+            kua_number(2024, Gender::Female),
+            3
+        );
+        assert_eq!(kua_number(1990, Gender::Male), 1);
+        assert_eq!(
+            kua_number(1990, Gender::Female),
+            8
+        );
+        // 5 is replaced with 8 for women.
+        assert_eq!(
+            kua_number(1981, Gender::Female),
+            8
+        );
+        assert_eq!(kua_number(1986, Gender::Male), 2);
+    }
+
+    #[test]
+    fn test_kua_from_number() {
+        assert!(kua_from_number(5).is_none());
+        assert!(kua_from_number(10).is_none());
+
+        let kua = kua_from_number(1).unwrap();
+        assert_eq!(kua.gua.direction, "n");
+        assert_eq!(kua.group, BaZhaiGroup::East);
+        assert_eq!(kua.directions.len(), 8);
+        assert_eq!(
+            kua.directions[3].kind,
+            BaZhaiStarKind::FuWei
+        );
+        assert_eq!(kua.directions[3].direction, "n");
+        assert_eq!(
+            kua.directions[7].gua.name.en,
+            "kun"
+        );
+        assert!(!kua.directions[7].auspicious);
+
+        for num in [1, 2, 3, 4, 6, 7, 8, 9] {
+            let kua = kua_from_number(num).unwrap();
+            for dir in DIRECTIONS.iter() {
+                assert_eq!(
+                    kua.directions
+                        .iter()
+                        .filter(
+                            |d| d.direction == *dir
+                        )
+                        .count(),
+                    1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_kua_before_lichun() {
+        // Born before 立春 (Li-Chun) belongs to 1983.
+        let kua = get_kua(
+            NaiveDate::from_ymd(1984, 2, 1)
+                .and_hms(12, 0, 0),
+            Gender::Male,
+        );
+        assert_eq!(kua.num, 8);
+    }
+
+    #[test]
+    fn test_get_kua_at_lichun() {
+        // 立春 (Li-Chun) in 2024 is about 08:27 (UT)
+        // on February 4th. Earlier on the same day
+        // still belongs to 2023.
+        let before = get_kua(
+            NaiveDate::from_ymd(2024, 2, 4)
+                .and_hms(2, 0, 0),
+            Gender::Male,
+        );
+        assert_eq!(before.num, 4);
+        let after = get_kua(
+            NaiveDate::from_ymd(2024, 2, 4)
+                .and_hms(12, 0, 0),
+            Gender::Male,
+        );
+        assert_eq!(after.num, 3);
+    }
+
+    #[test]
+    fn test_get_house_chart() {
+        // 坎宅 (Kan-Zhai) sitting north.
+        let chart =
+            get_house_chart(-5.0, "s").unwrap();
+        assert_eq!(chart.kua.num, 1);
+        assert_eq!(
+            chart.sitting,
+            Direction::new("n", 2)
+        );
+        assert_eq!(chart.facing, "s");
+        assert_eq!(chart.palaces.len(), 9);
+
+        // Laid out for "s", "se" comes top left.
+        assert_eq!(chart.palaces[0].direction, "se");
+        assert_eq!(
+            chart.palaces[0]
+                .star
+                .as_ref()
+                .unwrap()
+                .kind,
+            BaZhaiStarKind::ShengQi
+        );
+        assert!(chart.palaces[4].star.is_none());
+        assert_eq!(
+            chart
+                .get("n")
+                .unwrap()
+                .star
+                .as_ref()
+                .unwrap()
+                .kind,
+            BaZhaiStarKind::FuWei
+        );
+
+        assert!(
+            get_house_chart(f32::NAN, "n").is_err()
+        );
+        assert!(get_house_chart(0.0, "x").is_err());
+    }
+
+    #[test]
+    fn test_house_chart_overlay() {
+        // 乾宅 (Qian-Zhai) and a person with 1 (坎).
+        let chart =
+            get_house_chart(315.0, "n").unwrap();
+        let kua = kua_from_number(1).unwrap();
+        assert!(!chart.matches_group(&kua));
+        assert_eq!(
+            chart.get("n").unwrap().is_compatible(),
+            None
+        );
+
+        let chart = chart.overlay(&kua);
+        assert_eq!(
+            chart.get("").unwrap().is_compatible(),
+            None
+        );
+        assert_eq!(
+            chart.get("se").unwrap().is_compatible(),
+            Some(true)
+        );
+        assert_eq!(
+            chart.get("sw").unwrap().is_compatible(),
+            Some(false)
+        );
+    }
+}
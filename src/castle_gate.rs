@@ -0,0 +1,208 @@
+//! 城門訣 (Cheng-Men Jue), or "the Castle Gate
+//! formula", is a technique in 玄空飞星風水
+//! (Xuan-Kong Fei-Xing Feng-Shui) for finding a
+//! secondary entrance for water (or for a door) which
+//! brings 旺氣 (prosperous energy) even when the
+//! facing itself does not.
+//!
+//! The candidates are the two palaces next to the
+//! facing. Of the two, the one which makes 生成
+//! (Sheng-Cheng) pair (1-6, 2-7, 3-8, or 4-9) with the
+//! facing in 洛書 (Lo-Shu) is called 正城門
+//! (Zheng Cheng-Men), and the other is called 副城門
+//! (Fu Cheng-Men).
+//!
+//! To see whether the gate is usable, 運盤星 (Un-Pan
+//! Xing) of the gate is put in the center, and flown
+//! in normal or reverse order by its 陰陽 (Yin-Yang)
+//! for the same sector as the facing. When the star of
+//! the current period comes back to the gate, the gate
+//! is usable.
+
+use serde::{Deserialize, Serialize};
+
+use crate::compass::DIRECTION_POSITIONS_IN_CHART;
+use crate::jiuxing::{
+    fly_stars, is_shan_xiang_flying_normal, Flight,
+    JiuXingError, NatalChart,
+};
+
+/// Kinds of 城門 (Cheng-Men).
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum CastleGateKind {
+    Main,      // 正城門
+    Secondary, // 副城門
+}
+
+/// A struct representing 城門 (Cheng-Men).
+/// `direction` is that of the gate, `star` is 運盤星
+/// (Un-Pan Xing) index of the gate (which is the star
+/// activated when the gate is used), and `flight` is
+/// the order the star flies. `effective` is `true`
+/// when the star of the current period comes back to
+/// the gate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CastleGate {
+    pub kind: CastleGateKind,
+    pub direction: String,
+    pub star: usize,
+    pub flight: Flight,
+    pub effective: bool,
+}
+
+/// 九星 (Jiu-Xing) index in the original palace for the
+/// given direction (when 五黄土星 (5 Yellow) is in the
+/// center).
+fn home_star(
+    dir: &str,
+) -> Result<usize, JiuXingError> {
+    fly_stars(4, "n", Flight::Forward)?
+        .get(dir)
+        .ok_or_else(|| {
+            JiuXingError::InvalidDirection(dir.into())
+        })
+}
+
+/// Evaluates 城門 (Cheng-Men) in the given direction.
+fn castle_gate(
+    chart: &NatalChart,
+    dir: &str,
+) -> Result<CastleGate, JiuXingError> {
+    let period: usize = chart.period.center();
+    let star: usize =
+        chart.period.get(dir).ok_or_else(|| {
+            JiuXingError::InvalidDirection(dir.into())
+        })?;
+
+    // Just like 山星 (Shan-Xing) and 向星
+    // (Xiang-Xing), 五黄土星 (5 Yellow) follows
+    // 運盤星 (Un-Pan Xing) in the center.
+    let original: usize =
+        if star == 4 { period } else { star };
+    let flight: Flight =
+        if is_shan_xiang_flying_normal(
+            original,
+            chart.sector,
+        ) {
+            Flight::Forward
+        } else {
+            Flight::Reverse
+        };
+    let effective: bool =
+        fly_stars(star, "n", flight)?.get(dir)
+            == Some(period);
+
+    // 生成 (Sheng-Cheng) pairs differ by 5.
+    let diff: i32 = home_star(dir)? as i32
+        - home_star(&chart.facing_direction)? as i32;
+    let kind: CastleGateKind = match diff.abs() {
+        5 => CastleGateKind::Main,
+        _ => CastleGateKind::Secondary,
+    };
+
+    Ok(CastleGate {
+        kind,
+        direction: dir.to_string(),
+        star,
+        flight,
+        effective,
+    })
+}
+
+/// Returns the two 城門 (Cheng-Men) for the given
+/// `NatalChart` (正城門 (Zheng Cheng-Men) comes first).
+/// To find out which gate is usable, see `effective`.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::castle_gate::{get_castle_gates, CastleGate, CastleGateKind};
+/// use mikaboshi::jiuxing::get_natal_chart;
+///
+/// // 八運 (Period 8), 子山午向
+/// let chart = get_natal_chart(7, "s", 2, "n").unwrap();
+/// let gates: Vec<CastleGate> = get_castle_gates(&chart).unwrap();
+/// let gate: &CastleGate = gates.iter().find(|g| g.effective).unwrap();
+/// assert_eq!(gate.kind, CastleGateKind::Main);
+/// assert_eq!(gate.direction, "se");
+/// ```
+pub fn get_castle_gates(
+    chart: &NatalChart,
+) -> Result<Vec<CastleGate>, JiuXingError> {
+    let facing: &str = &chart.facing_direction;
+
+    // When laid out for the facing, the facing comes
+    // to the top in the middle, and the two palaces
+    // next to it come to the top left and top right.
+    let positions: &[&str; 9] =
+        DIRECTION_POSITIONS_IN_CHART
+            .get(facing)
+            .ok_or_else(|| {
+                JiuXingError::InvalidDirection(
+                    facing.to_string(),
+                )
+            })?;
+
+    let mut gates: Vec<CastleGate> = vec![
+        castle_gate(chart, positions[0])?,
+        castle_gate(chart, positions[2])?,
+    ];
+    gates.sort_by_key(|gate| {
+        gate.kind == CastleGateKind::Secondary
+    });
+    Ok(gates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jiuxing::get_natal_chart;
+
+    #[test]
+    fn test_get_castle_gates() {
+        // 八運 (Period 8), 子山午向
+        let chart =
+            get_natal_chart(7, "s", 2, "n").unwrap();
+        let gates = get_castle_gates(&chart).unwrap();
+        assert_eq!(gates.len(), 2);
+
+        assert_eq!(
+            gates[0].kind,
+            CastleGateKind::Main
+        );
+        assert_eq!(gates[0].direction, "se");
+        assert_eq!(gates[0].star, 6);
+        assert_eq!(gates[0].flight, Flight::Reverse);
+        assert!(gates[0].effective);
+
+        // 五黄土星 (5 Yellow) follows 八白土星
+        // (8 White) in the center.
+        assert_eq!(
+            gates[1].kind,
+            CastleGateKind::Secondary
+        );
+        assert_eq!(gates[1].direction, "sw");
+        assert_eq!(gates[1].star, 4);
+        assert_eq!(gates[1].flight, Flight::Forward);
+        assert!(!gates[1].effective);
+    }
+
+    #[test]
+    fn test_get_castle_gates_for_north() {
+        // 八運 (Period 8), 午山子向
+        let chart =
+            get_natal_chart(7, "n", 2, "n").unwrap();
+        let gates = get_castle_gates(&chart).unwrap();
+        assert_eq!(gates[0].direction, "nw");
+        assert!(gates[0].effective);
+        assert_eq!(gates[1].direction, "ne");
+        assert!(!gates[1].effective);
+    }
+}
@@ -13,6 +13,11 @@ use crate::bagua::{
     Gua, BAGUA_LO_SHU_ORDER_WITH_CENTER,
 };
 use crate::ganzhi::{Branch, Stem, BRANCHES, STEMS};
+use crate::language::{
+    Language, LanguageData, LanguageTrait,
+    NameDataTrait,
+};
+use crate::utils::get_json;
 
 /// 二十四山向 (Er-Shi-Si Shan-Xiang) can be
 /// either 卦 (Gua), 干 (Gan), or 支 (Zhi).
@@ -142,6 +147,70 @@ pub fn get_opposite_direction(dir: &str) -> &str {
     OPPOSITE_DIRECTION[dir]
 }
 
+/// A struct representing the name of a direction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectionName {
+    pub direction: String,
+    pub name: Language,
+}
+
+/// A temporary struct for loading JSON data when
+/// defining a static const `DIRECTION_NAMES`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectionNameRawData {
+    pub direction: String,
+    pub name: LanguageData,
+}
+
+impl LanguageTrait for DirectionName {
+    fn name(&self) -> Box<Language> {
+        Box::new(self.name.clone())
+    }
+}
+
+impl NameDataTrait for DirectionNameRawData {
+    fn name(&self) -> Box<LanguageData> {
+        Box::new(self.name.clone())
+    }
+}
+
+lazy_static! {
+    /// A static vector with 8 items, each represents
+    /// the name of a direction (in the order of
+    /// `DIRECTIONS`).
+    ///
+    /// For attributes details stored in the vector is
+    /// found in JSON file:
+    /// `src/json/directions.json`
+    pub static ref DIRECTION_NAMES: Vec<DirectionName> = {
+        let json = &include_str!("../json/directions.json");
+        let data: Vec<DirectionNameRawData> =
+            get_json::<DirectionNameRawData>(json);
+        data.iter()
+            .map(|item| DirectionName {
+                direction: item.direction.clone(),
+                name: item.language_from_data(),
+            })
+            .collect()
+    };
+}
+
+/// Returns `DirectionName` for the given direction.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::compass::get_direction_name;
+///
+/// assert_eq!(get_direction_name("se").unwrap().name.zh_tw.alphabet, "東南");
+/// ```
+pub fn get_direction_name(
+    dir: &str,
+) -> Option<&'static DirectionName> {
+    DIRECTION_NAMES
+        .iter()
+        .find(|name| name.direction == dir)
+}
+
 /// An array with 24 items. Imagine having a circlar
 /// disc displayed on a device screen. When dividing 360
 /// by 8 directions, we get 45 degrees for each. When
@@ -484,6 +553,25 @@ mod tests {
 
     // TODO: OPPOSITE_DIRECTION
     // TODO: get_opposite_direction
+
+    #[test]
+    fn test_constant_direction_names() {
+        assert_eq!(DIRECTION_NAMES.len(), 8);
+        for (name, dir) in DIRECTION_NAMES
+            .iter()
+            .zip(DIRECTIONS.iter())
+        {
+            assert_eq!(name.direction, *dir);
+        }
+        assert_eq!(
+            get_direction_name("nw")
+                .unwrap()
+                .alphabet(),
+            "西北"
+        );
+        assert!(get_direction_name("").is_none());
+    }
+
     // TODO: TWENTYFOUR_SECTORS
 
     #[test]
@@ -0,0 +1,357 @@
+//! A module for the position of the sun. Every
+//! astronomical calculation in this library (such as
+//! 二十四节气 (Er-Shi-Si Jie-Qi), 立春 (Li-Chun), the
+//! month pillar for 八字 (Bazi), or 三元九運
+//! (Sang-Yuan Jiu-Yun)) eventually needs to know the
+//! longitude of the sun, and all of them ask
+//! `SunLongitudeProvider` for it.
+//!
+//! By default, `SowngwalaProvider` is used which
+//! calculates the position of the sun using
+//! ["sowngwala"](https://github.com/minagawah/sowngwala/).
+//! However, you may implement `SunLongitudeProvider`
+//! for your own, either for higher precision, or
+//! for cross-checking the results, or for having
+//! deterministic test doubles. Functions which end
+//! with `_with` (ex. `get_lichun_with`) take the
+//! provider as the first argument.
+//!
+//! Also, notice that the position of the sun is
+//! calculated in Terrestrial Time (TT) whereas civil
+//! time is in Universal Time (UT). The difference
+//! (known as ΔT) must be applied especially for
+//! historical charts, and `DeltaTModel` is the switch.
+
+use chrono::naive::{NaiveDate, NaiveDateTime};
+use chrono::{Datelike, Duration, Timelike};
+use serde::{Deserialize, Serialize};
+use sowngwala::sun::ecliptic_position_of_the_sun_from_generic_date;
+use sowngwala::time::add_date;
+
+/// A trait for providing the ecliptic longitude of
+/// the sun (in degrees, from 0 to 360). Given date and
+/// time are those of UT.
+pub trait SunLongitudeProvider {
+    /// Returns the longitude of the sun for the given
+    /// date and time.
+    fn longitude_of_the_sun(
+        &self,
+        datetime: NaiveDateTime,
+    ) -> f64;
+
+    /// Returns the longitude of the sun for the given
+    /// date (at 0h).
+    fn longitude_of_the_sun_from_date(
+        &self,
+        date: NaiveDate,
+    ) -> f64 {
+        self.longitude_of_the_sun(
+            date.and_hms(0, 0, 0),
+        )
+    }
+}
+
+/// Models for ΔT (Delta T), which is the difference
+/// between Terrestrial Time (TT) and Universal Time
+/// (UT). While we measure civil time in UT, positions
+/// of the sun are calculated in TT. ΔT is a little
+/// more than a minute today, but it grows to hours
+/// in antiquity.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Default,
+)]
+pub enum DeltaTModel {
+    /// Treats UT as TT.
+    Ignore,
+    /// Polynomial expressions by Fred Espenak and
+    /// Jean Meeus (used in NASA's "Five Millennium
+    /// Canon of Solar Eclipses").
+    #[default]
+    EspenakMeeus,
+}
+
+impl DeltaTModel {
+    /// Returns ΔT (in seconds) for the given date and
+    /// time (UT).
+    pub fn seconds(
+        &self,
+        datetime: NaiveDateTime,
+    ) -> f64 {
+        match self {
+            DeltaTModel::Ignore => 0.0,
+            DeltaTModel::EspenakMeeus => {
+                delta_t_espenak_meeus(
+                    datetime.year() as f64
+                        + (datetime.month() as f64
+                            - 0.5)
+                            / 12.0,
+                )
+            }
+        }
+    }
+
+    /// Converts the given date and time from UT to TT.
+    pub fn to_terrestrial_time(
+        &self,
+        datetime: NaiveDateTime,
+    ) -> NaiveDateTime {
+        datetime
+            + Duration::milliseconds(
+                (self.seconds(datetime) * 1000.0)
+                    .round() as i64,
+            )
+    }
+}
+
+/// ΔT (in seconds) for the given decimal year using
+/// polynomial expressions by Fred Espenak and Jean
+/// Meeus.
+///
+/// Reference:
+/// - [Polynomial Expressions for Delta T - NASA](https://eclipse.gsfc.nasa.gov/SEcat5/deltatpoly.html)
+pub fn delta_t_espenak_meeus(y: f64) -> f64 {
+    if y < -500.0 {
+        let u = (y - 1820.0) / 100.0;
+        -20.0 + 32.0 * u.powi(2)
+    } else if y < 500.0 {
+        let u = y / 100.0;
+        10583.6 - 1014.41 * u + 33.78311 * u.powi(2)
+            - 5.952053 * u.powi(3)
+            - 0.1798452 * u.powi(4)
+            + 0.022174192 * u.powi(5)
+            + 0.0090316521 * u.powi(6)
+    } else if y < 1600.0 {
+        let u = (y - 1000.0) / 100.0;
+        1574.2 - 556.01 * u
+            + 71.23472 * u.powi(2)
+            + 0.319781 * u.powi(3)
+            - 0.8503463 * u.powi(4)
+            - 0.005050998 * u.powi(5)
+            + 0.0083572073 * u.powi(6)
+    } else if y < 1700.0 {
+        let t = y - 1600.0;
+        120.0 - 0.9808 * t - 0.01532 * t.powi(2)
+            + t.powi(3) / 7129.0
+    } else if y < 1800.0 {
+        let t = y - 1700.0;
+        8.83 + 0.1603 * t - 0.0059285 * t.powi(2)
+            + 0.00013336 * t.powi(3)
+            - t.powi(4) / 1174000.0
+    } else if y < 1860.0 {
+        let t = y - 1800.0;
+        13.72 - 0.332447 * t
+            + 0.0068612 * t.powi(2)
+            + 0.0041116 * t.powi(3)
+            - 0.00037436 * t.powi(4)
+            + 0.0000121272 * t.powi(5)
+            - 0.0000001699 * t.powi(6)
+            + 0.000000000875 * t.powi(7)
+    } else if y < 1900.0 {
+        let t = y - 1860.0;
+        7.62 + 0.5737 * t - 0.251754 * t.powi(2)
+            + 0.01680668 * t.powi(3)
+            - 0.0004473624 * t.powi(4)
+            + t.powi(5) / 233174.0
+    } else if y < 1920.0 {
+        let t = y - 1900.0;
+        -2.79 + 1.494119 * t - 0.0598939 * t.powi(2)
+            + 0.0061966 * t.powi(3)
+            - 0.000197 * t.powi(4)
+    } else if y < 1941.0 {
+        let t = y - 1920.0;
+        21.20 + 0.84493 * t - 0.076100 * t.powi(2)
+            + 0.0020936 * t.powi(3)
+    } else if y < 1961.0 {
+        let t = y - 1950.0;
+        29.07 + 0.407 * t - t.powi(2) / 233.0
+            + t.powi(3) / 2547.0
+    } else if y < 1986.0 {
+        let t = y - 1975.0;
+        45.45 + 1.067 * t
+            - t.powi(2) / 260.0
+            - t.powi(3) / 718.0
+    } else if y < 2005.0 {
+        let t = y - 2000.0;
+        63.86 + 0.3345 * t - 0.060374 * t.powi(2)
+            + 0.0017275 * t.powi(3)
+            + 0.000651814 * t.powi(4)
+            + 0.00002373599 * t.powi(5)
+    } else if y < 2050.0 {
+        let t = y - 2000.0;
+        62.92 + 0.32217 * t + 0.005589 * t.powi(2)
+    } else if y < 2150.0 {
+        let u = (y - 1820.0) / 100.0;
+        -20.0 + 32.0 * u.powi(2)
+            - 0.5628 * (2150.0 - y)
+    } else {
+        let u = (y - 1820.0) / 100.0;
+        -20.0 + 32.0 * u.powi(2)
+    }
+}
+
+/// The default `SunLongitudeProvider` which uses
+/// ["sowngwala"](https://github.com/minagawah/sowngwala/).
+/// Given date and time (UT) are first converted to
+/// TT using `delta_t`. Since "sowngwala" accepts only
+/// a date, for the given time of the day, it linearly
+/// interpolates the longitude between the date and
+/// the next.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SowngwalaProvider {
+    pub delta_t: DeltaTModel,
+}
+
+impl SowngwalaProvider {
+    pub const fn new(delta_t: DeltaTModel) -> Self {
+        SowngwalaProvider { delta_t }
+    }
+}
+
+impl SunLongitudeProvider for SowngwalaProvider {
+    fn longitude_of_the_sun(
+        &self,
+        datetime: NaiveDateTime,
+    ) -> f64 {
+        let tt: NaiveDateTime = self
+            .delta_t
+            .to_terrestrial_time(datetime);
+        let date: NaiveDate = tt.date();
+        let lng_0: f64 =
+            ecliptic_position_of_the_sun_from_generic_date(
+                date,
+            )
+            .lng;
+        let lng_1: f64 =
+            ecliptic_position_of_the_sun_from_generic_date(
+                add_date(date, 1),
+            )
+            .lng;
+
+        // The sun moves roughly 1 degree a day,
+        // but we need to watch out when it goes
+        // past 360 degrees.
+        let motion: f64 =
+            (lng_1 - lng_0).rem_euclid(360.0);
+        let fraction: f64 =
+            tt.num_seconds_from_midnight() as f64
+                / 86400.0;
+
+        (lng_0 + motion * fraction).rem_euclid(360.0)
+    }
+}
+
+/// The provider used when no provider is specified.
+/// ΔT is applied using `DeltaTModel::EspenakMeeus`.
+pub const DEFAULT_PROVIDER: SowngwalaProvider =
+    SowngwalaProvider::new(DeltaTModel::EspenakMeeus);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sowngwala_provider_from_date() {
+        let date = NaiveDate::from_ymd(2022, 2, 4);
+        assert_eq!(
+            DEFAULT_PROVIDER
+                .longitude_of_the_sun_from_date(date),
+            DEFAULT_PROVIDER.longitude_of_the_sun(
+                date.and_hms(0, 0, 0)
+            )
+        );
+    }
+
+    #[test]
+    fn test_sowngwala_provider_interpolates() {
+        let date = NaiveDate::from_ymd(2022, 2, 4);
+        let lng_0 = DEFAULT_PROVIDER
+            .longitude_of_the_sun(
+                date.and_hms(0, 0, 0),
+            );
+        let lng_12 = DEFAULT_PROVIDER
+            .longitude_of_the_sun(
+                date.and_hms(12, 0, 0),
+            );
+        let lng_24 = DEFAULT_PROVIDER
+            .longitude_of_the_sun_from_date(
+                add_date(date, 1),
+            );
+        assert!(lng_0 < lng_12 && lng_12 < lng_24);
+    }
+
+    #[test]
+    fn test_sowngwala_provider_wraps_around() {
+        // 春分 (Chun-Fen) in 2022 is 15:33 (UT).
+        let lng = DEFAULT_PROVIDER
+            .longitude_of_the_sun(
+                NaiveDate::from_ymd(2022, 3, 20)
+                    .and_hms(23, 0, 0),
+            );
+        assert!((0.0..1.0).contains(&lng));
+    }
+
+    #[test]
+    fn test_delta_t_espenak_meeus() {
+        assert!(
+            (delta_t_espenak_meeus(2000.0) - 63.86)
+                .abs()
+                < 0.01
+        );
+        assert!(
+            (delta_t_espenak_meeus(1000.0) - 1574.2)
+                .abs()
+                < 0.01
+        );
+        // It is roughly 3 hours at the beginning of
+        // the Common Era.
+        let hours =
+            delta_t_espenak_meeus(0.0) / 3600.0;
+        assert!((2.9..3.0).contains(&hours));
+    }
+
+    #[test]
+    fn test_delta_t_model() {
+        let datetime =
+            NaiveDate::from_ymd(2000, 1, 1)
+                .and_hms(0, 0, 0);
+        assert_eq!(
+            DeltaTModel::Ignore
+                .to_terrestrial_time(datetime),
+            datetime
+        );
+        assert_eq!(
+            (DeltaTModel::EspenakMeeus
+                .to_terrestrial_time(datetime)
+                - datetime)
+                .num_seconds(),
+            63
+        );
+    }
+
+    #[test]
+    fn test_sowngwala_provider_switches_delta_t() {
+        let datetime =
+            NaiveDate::from_ymd(1000, 3, 1)
+                .and_hms(0, 0, 0);
+        let ignore = SowngwalaProvider::new(
+            DeltaTModel::Ignore,
+        )
+        .longitude_of_the_sun(datetime);
+        let applied = SowngwalaProvider::new(
+            DeltaTModel::EspenakMeeus,
+        )
+        .longitude_of_the_sun(datetime);
+
+        // ΔT is about 26 minutes for the year 1000, and
+        // the sun moves about 0.018 degrees meanwhile.
+        let diff = applied - ignore;
+        assert!((0.015..0.02).contains(&diff));
+    }
+}
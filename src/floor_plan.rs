@@ -0,0 +1,741 @@
+//! A module for placing rooms of a floor plan onto
+//! palaces, so that readings from charts (for
+//! instance, those of `jiuxing::NatalChart`) can be
+//! made for each room.
+//!
+//! The floor plan is given in its own coordinates
+//! (`x` to the right, and `y` upward), and is expected
+//! to have its facing side toward `y` (upward). `facing`
+//! is the compass bearing of the facing (in degrees,
+//! clockwise from the north).
+//!
+//! There are 2 ways to divide the plan:
+//!
+//! (1) 九宮格 (Jiu-Gong-Ge) --> The bounding box of the
+//! plan is divided into 3x3 palaces aligned to the
+//! facing. Palaces are laid out in the same manner as
+//! `DIRECTION_POSITIONS_IN_CHART` for the facing
+//! direction (the facing comes to the top middle).
+//! (2) Sectors --> The plan is divided into 8 (45
+//! degrees each) or 24 (15 degrees each) pie sectors
+//! from the center (which is the centroid of the plan
+//! unless specified otherwise).
+//!
+//! Rooms are rectangles in the same coordinates, and
+//! each room gets palaces it overlaps with fractions of
+//! its area.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::compass::{
+    get_twentyfour_direction_from_degrees,
+    DIRECTIONS, DIRECTION_POSITIONS_IN_CHART,
+    TWENTYFOUR_INDEX_TO_DIRECTIONS,
+};
+
+/// Overlaps smaller than this (as a fraction of the
+/// room) are ignored.
+const MIN_FRACTION: f64 = 1e-9;
+
+/// A point on the floor plan.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Serialize,
+    Deserialize,
+)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+}
+
+/// A room given as a rectangle. `x` and `y` are those
+/// of the lower left corner.
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize,
+)]
+pub struct Room {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Room {
+    pub fn new(
+        name: &str,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    ) -> Self {
+        Room {
+            name: name.to_string(),
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn corners(&self) -> Vec<Point> {
+        vec![
+            Point::new(self.x, self.y),
+            Point::new(self.x + self.width, self.y),
+            Point::new(
+                self.x + self.width,
+                self.y + self.height,
+            ),
+            Point::new(self.x, self.y + self.height),
+        ]
+    }
+}
+
+/// A floor plan. `outline` is the polygon of the plan,
+/// and `facing` is the compass bearing of `y` (upward)
+/// of the plan. `center` is used for sectors, and the
+/// centroid of `outline` is used when `None`.
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize,
+)]
+pub struct FloorPlan {
+    pub outline: Vec<Point>,
+    pub rooms: Vec<Room>,
+    pub facing: f32,
+    pub center: Option<Point>,
+}
+
+/// How the floor plan is divided.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum Division {
+    NinePalaces,       // 九宮格
+    EightSectors,      // 八方位
+    TwentyFourSectors, // 二十四山
+}
+
+/// A palace of 九宮格 (Jiu-Gong-Ge). `direction` is
+/// that of the palace ("" being the center), and `min`
+/// and `max` are the corners of the palace.
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize,
+)]
+pub struct GridPalace {
+    pub direction: String,
+    pub min: Point,
+    pub max: Point,
+}
+
+/// A palace which the room overlaps. `direction` is
+/// that of the palace ("" being the center), and
+/// `mountain` is the index for
+/// `TWENTYFOUR_INDEX_TO_DIRECTIONS` (only for
+/// `Division::TwentyFourSectors`). `fraction` is the
+/// area of the overlap divided by the area of the room.
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize,
+)]
+pub struct Overlap {
+    pub direction: String,
+    pub mountain: Option<usize>,
+    pub fraction: f64,
+}
+
+/// A struct representing palaces for the room.
+/// `overlaps` are sorted by `fraction` (the largest
+/// first).
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize,
+)]
+pub struct RoomPlacement {
+    pub name: String,
+    pub area: f64,
+    pub overlaps: Vec<Overlap>,
+}
+
+/// Errors for the floor plan.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FloorPlanError {
+    /// The outline must have 3 or more points, and
+    /// must have an area.
+    InvalidOutline,
+    /// Rooms must have positive width and height.
+    InvalidRoom(String),
+    /// Degrees must be a finite number.
+    InvalidDegrees(f32),
+}
+
+impl fmt::Display for FloorPlanError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        match self {
+            FloorPlanError::InvalidOutline => {
+                write!(f, "Invalid outline")
+            }
+            FloorPlanError::InvalidRoom(name) => {
+                write!(f, "Invalid room: {}", name)
+            }
+            FloorPlanError::InvalidDegrees(
+                degrees,
+            ) => {
+                write!(
+                    f,
+                    "Invalid degrees: {}",
+                    degrees
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FloorPlanError {}
+
+/// Signed area of the polygon (positive for
+/// counterclockwise).
+fn signed_area(polygon: &[Point]) -> f64 {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Returns the centroid of the polygon. Returns `None`
+/// when the polygon has no area.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::floor_plan::{centroid, Point};
+///
+/// let square = [
+///     Point::new(0.0, 0.0),
+///     Point::new(4.0, 0.0),
+///     Point::new(4.0, 4.0),
+///     Point::new(0.0, 4.0),
+/// ];
+/// assert_eq!(centroid(&square), Some(Point::new(2.0, 2.0)));
+/// ```
+pub fn centroid(polygon: &[Point]) -> Option<Point> {
+    let area: f64 = signed_area(polygon);
+    if polygon.len() < 3 || area.abs() < f64::EPSILON
+    {
+        return None;
+    }
+    let (x, y) = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .fold((0.0, 0.0), |(x, y), (a, b)| {
+            let cross: f64 = a.x * b.y - b.x * a.y;
+            (
+                x + (a.x + b.x) * cross,
+                y + (a.y + b.y) * cross,
+            )
+        });
+    Some(Point::new(
+        x / (6.0 * area),
+        y / (6.0 * area),
+    ))
+}
+
+/// Clips the polygon by the half plane on the left of
+/// the edge from `a` to `b` (Sutherland-Hodgman).
+fn clip_by_edge(
+    polygon: &[Point],
+    a: Point,
+    b: Point,
+) -> Vec<Point> {
+    let side = |p: &Point| {
+        (b.x - a.x) * (p.y - a.y)
+            - (b.y - a.y) * (p.x - a.x)
+    };
+    let mut clipped: Vec<Point> = vec![];
+    for (p, q) in polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+    {
+        let (sp, sq) = (side(p), side(q));
+        if sp >= 0.0 {
+            clipped.push(*p);
+        }
+        if (sp >= 0.0) != (sq >= 0.0) {
+            let t: f64 = sp / (sp - sq);
+            clipped.push(Point::new(
+                p.x + (q.x - p.x) * t,
+                p.y + (q.y - p.y) * t,
+            ));
+        }
+    }
+    clipped
+}
+
+/// Area of the intersection of the polygon and the
+/// convex polygon (counterclockwise).
+fn intersection_area(
+    polygon: &[Point],
+    convex: &[Point],
+) -> f64 {
+    let clipped: Vec<Point> = convex
+        .iter()
+        .zip(convex.iter().cycle().skip(1))
+        .fold(polygon.to_vec(), |acc, (a, b)| {
+            clip_by_edge(&acc, *a, *b)
+        });
+    if clipped.len() < 3 {
+        0.0
+    } else {
+        signed_area(&clipped).abs()
+    }
+}
+
+fn validate(
+    plan: &FloorPlan,
+) -> Result<(), FloorPlanError> {
+    if !plan.facing.is_finite() {
+        return Err(FloorPlanError::InvalidDegrees(
+            plan.facing,
+        ));
+    }
+    if centroid(&plan.outline).is_none() {
+        return Err(FloorPlanError::InvalidOutline);
+    }
+    match plan.rooms.iter().find(|room| {
+        !(room.width > 0.0 && room.height > 0.0)
+    }) {
+        Some(room) => {
+            Err(FloorPlanError::InvalidRoom(
+                room.name.clone(),
+            ))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Returns 9 palaces of 九宮格 (Jiu-Gong-Ge) for the
+/// floor plan. They are in the order of
+/// `DIRECTION_POSITIONS_IN_CHART` for the facing
+/// direction (from the top left to the bottom right).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::floor_plan::{get_grid_palaces, FloorPlan, Point};
+///
+/// let plan = FloorPlan {
+///     outline: vec![
+///         Point::new(0.0, 0.0),
+///         Point::new(9.0, 0.0),
+///         Point::new(9.0, 6.0),
+///         Point::new(0.0, 6.0),
+///     ],
+///     rooms: vec![],
+///     facing: 180.0,
+///     center: None,
+/// };
+/// let palaces = get_grid_palaces(&plan).unwrap();
+/// // The facing (south) comes to the top middle.
+/// assert_eq!(palaces[1].direction, "s");
+/// assert_eq!(palaces[1].min, Point::new(3.0, 4.0));
+/// assert_eq!(palaces[1].max, Point::new(6.0, 6.0));
+/// ```
+pub fn get_grid_palaces(
+    plan: &FloorPlan,
+) -> Result<Vec<GridPalace>, FloorPlanError> {
+    validate(plan)?;
+    let facing =
+        get_twentyfour_direction_from_degrees(
+            plan.facing.rem_euclid(360.0),
+        );
+    // Always found for the 8 directions.
+    let positions: &[&str; 9] =
+        DIRECTION_POSITIONS_IN_CHART
+            .get(facing.direction.as_str())
+            .unwrap();
+
+    let xs = plan.outline.iter().map(|p| p.x);
+    let ys = plan.outline.iter().map(|p| p.y);
+    let min_x: f64 =
+        xs.clone().fold(f64::INFINITY, f64::min);
+    let max_x: f64 =
+        xs.fold(f64::NEG_INFINITY, f64::max);
+    let min_y: f64 =
+        ys.clone().fold(f64::INFINITY, f64::min);
+    let max_y: f64 =
+        ys.fold(f64::NEG_INFINITY, f64::max);
+    let w: f64 = (max_x - min_x) / 3.0;
+    let h: f64 = (max_y - min_y) / 3.0;
+
+    Ok(positions
+        .iter()
+        .enumerate()
+        .map(|(i, dir)| {
+            // The first row is at the top.
+            let col = (i % 3) as f64;
+            let row = (2 - i / 3) as f64;
+            GridPalace {
+                direction: dir.to_string(),
+                min: Point::new(
+                    min_x + w * col,
+                    min_y + h * row,
+                ),
+                max: Point::new(
+                    min_x + w * (col + 1.0),
+                    min_y + h * (row + 1.0),
+                ),
+            }
+        })
+        .collect())
+}
+
+/// A pie sector as a triangle (counterclockwise) with
+/// its apex at `center`, and the radius of `radius`.
+/// `start` and `end` are compass bearings.
+fn sector_triangle(
+    center: Point,
+    facing: f64,
+    start: f64,
+    end: f64,
+    radius: f64,
+) -> Vec<Point> {
+    // Compass bearings go clockwise on the plan where
+    // `y` (upward) is the facing.
+    let at = |bearing: f64| {
+        let rad: f64 =
+            (bearing - facing).to_radians();
+        Point::new(
+            center.x + radius * rad.sin(),
+            center.y + radius * rad.cos(),
+        )
+    };
+    vec![center, at(end), at(start)]
+}
+
+/// Returns `RoomPlacement` for each room of the floor
+/// plan divided by `division`.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::floor_plan::{place_rooms, Division, FloorPlan, Point, Room};
+///
+/// let plan = FloorPlan {
+///     outline: vec![
+///         Point::new(0.0, 0.0),
+///         Point::new(9.0, 0.0),
+///         Point::new(9.0, 9.0),
+///         Point::new(0.0, 9.0),
+///     ],
+///     rooms: vec![Room::new("kitchen", 0.0, 6.0, 4.5, 3.0)],
+///     facing: 0.0,
+///     center: None,
+/// };
+///
+/// let placements = place_rooms(&plan, Division::NinePalaces).unwrap();
+/// let kitchen = &placements[0];
+/// assert_eq!(kitchen.overlaps[0].direction, "nw");
+/// assert!((kitchen.overlaps[0].fraction - 2.0 / 3.0).abs() < 1e-9);
+/// assert_eq!(kitchen.overlaps[1].direction, "n");
+/// ```
+pub fn place_rooms(
+    plan: &FloorPlan,
+    division: Division,
+) -> Result<Vec<RoomPlacement>, FloorPlanError> {
+    validate(plan)?;
+
+    // Zones as convex polygons (counterclockwise).
+    let zones: Vec<(
+        String,
+        Option<usize>,
+        Vec<Point>,
+    )> = match division {
+        Division::NinePalaces => {
+            get_grid_palaces(plan)?
+                .into_iter()
+                .map(|palace| {
+                    let (a, b) =
+                        (palace.min, palace.max);
+                    (
+                        palace.direction,
+                        None,
+                        vec![
+                            a,
+                            Point::new(b.x, a.y),
+                            b,
+                            Point::new(a.x, b.y),
+                        ],
+                    )
+                })
+                .collect()
+        }
+        Division::EightSectors
+        | Division::TwentyFourSectors => {
+            sector_zones(plan, division)
+        }
+    };
+
+    Ok(plan
+        .rooms
+        .iter()
+        .map(|room| {
+            let corners: Vec<Point> = room.corners();
+            let area: f64 = room.width * room.height;
+            let mut overlaps: Vec<Overlap> = zones
+                .iter()
+                .map(|(direction, mountain, zone)| {
+                    Overlap {
+                        direction: direction.clone(),
+                        mountain: *mountain,
+                        fraction: intersection_area(
+                            &corners, zone,
+                        ) / area,
+                    }
+                })
+                .filter(|o| o.fraction > MIN_FRACTION)
+                .collect();
+            overlaps.sort_by(|a, b| {
+                b.fraction
+                    .partial_cmp(&a.fraction)
+                    .unwrap()
+            });
+            RoomPlacement {
+                name: room.name.clone(),
+                area,
+                overlaps,
+            }
+        })
+        .collect())
+}
+
+/// Pie sectors for 8 directions or 24 mountains.
+fn sector_zones(
+    plan: &FloorPlan,
+    division: Division,
+) -> Vec<(String, Option<usize>, Vec<Point>)> {
+    // Already validated.
+    let center: Point =
+        plan.center.unwrap_or_else(|| {
+            centroid(&plan.outline).unwrap()
+        });
+
+    // The triangle must reach beyond all the rooms.
+    let radius: f64 = plan
+        .rooms
+        .iter()
+        .flat_map(|room| room.corners())
+        .chain(plan.outline.iter().copied())
+        .map(|p| {
+            (p.x - center.x).hypot(p.y - center.y)
+        })
+        .fold(0.0, f64::max)
+        * 2.0
+        + 1.0;
+    let facing: f64 = plan.facing as f64;
+
+    match division {
+        Division::TwentyFourSectors => {
+            TWENTYFOUR_INDEX_TO_DIRECTIONS
+                .iter()
+                .enumerate()
+                .map(|(i, dir)| {
+                    let mid: f64 = i as f64 * 15.0;
+                    (
+                        dir.direction.clone(),
+                        Some(i),
+                        sector_triangle(
+                            center,
+                            facing,
+                            mid - 7.5,
+                            mid + 7.5,
+                            radius,
+                        ),
+                    )
+                })
+                .collect()
+        }
+        _ => DIRECTIONS
+            .iter()
+            .enumerate()
+            .map(|(i, dir)| {
+                let mid: f64 = i as f64 * 45.0;
+                (
+                    dir.to_string(),
+                    None,
+                    sector_triangle(
+                        center,
+                        facing,
+                        mid - 22.5,
+                        mid + 22.5,
+                        radius,
+                    ),
+                )
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square_plan(
+        facing: f32,
+        rooms: Vec<Room>,
+    ) -> FloorPlan {
+        FloorPlan {
+            outline: vec![
+                Point::new(0.0, 0.0),
+                Point::new(9.0, 0.0),
+                Point::new(9.0, 9.0),
+                Point::new(0.0, 9.0),
+            ],
+            rooms,
+            facing,
+            center: None,
+        }
+    }
+
+    #[test]
+    fn test_centroid() {
+        // L-shaped
+        let outline = [
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 2.0),
+            Point::new(0.0, 2.0),
+        ];
+        let c = centroid(&outline).unwrap();
+        assert!((c.x - 5.0 / 6.0).abs() < 1e-9);
+        assert!((c.y - 5.0 / 6.0).abs() < 1e-9);
+        assert_eq!(
+            centroid(&[Point::new(0.0, 0.0); 3]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_place_rooms_nine_palaces() {
+        // Facing the south, the north comes to the
+        // bottom middle.
+        let plan = square_plan(
+            180.0,
+            vec![Room::new(
+                "hall", 3.0, 0.0, 3.0, 3.0,
+            )],
+        );
+        let placements =
+            place_rooms(&plan, Division::NinePalaces)
+                .unwrap();
+        assert_eq!(placements[0].overlaps.len(), 1);
+        assert_eq!(
+            placements[0].overlaps[0].direction,
+            "n"
+        );
+        assert!(
+            (placements[0].overlaps[0].fraction
+                - 1.0)
+                .abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn test_place_rooms_sectors() {
+        // A room over the center.
+        let plan = square_plan(
+            0.0,
+            vec![Room::new(
+                "stairs", 3.5, 3.5, 2.0, 2.0,
+            )],
+        );
+        let placements = place_rooms(
+            &plan,
+            Division::EightSectors,
+        )
+        .unwrap();
+        let overlaps = &placements[0].overlaps;
+        assert_eq!(overlaps.len(), 8);
+        let total: f64 =
+            overlaps.iter().map(|o| o.fraction).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        // Cardinal directions get less of a square.
+        let tan: f64 = 22.5_f64.to_radians().tan();
+        for o in overlaps.iter() {
+            let expected: f64 =
+                if o.direction.len() == 1 {
+                    tan / 4.0
+                } else {
+                    (1.0 - tan) / 4.0
+                };
+            assert!(
+                (o.fraction - expected).abs() < 1e-9
+            );
+        }
+
+        // A room straight to the east of the center
+        // (facing the east, it is at the top).
+        let plan = square_plan(
+            90.0,
+            vec![Room::new(
+                "porch", 4.4, 8.0, 0.2, 1.0,
+            )],
+        );
+        let placements = place_rooms(
+            &plan,
+            Division::TwentyFourSectors,
+        )
+        .unwrap();
+        let overlaps = &placements[0].overlaps;
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].direction, "e");
+        assert_eq!(overlaps[0].mountain, Some(6));
+    }
+
+    #[test]
+    fn test_place_rooms_invalid() {
+        let plan = square_plan(
+            0.0,
+            vec![Room::new(
+                "void", 0.0, 0.0, 0.0, 1.0,
+            )],
+        );
+        assert_eq!(
+            place_rooms(&plan, Division::NinePalaces),
+            Err(FloorPlanError::InvalidRoom(
+                "void".into()
+            ))
+        );
+        let mut plan = square_plan(f32::NAN, vec![]);
+        assert!(matches!(
+            place_rooms(
+                &plan,
+                Division::EightSectors
+            ),
+            Err(FloorPlanError::InvalidDegrees(_))
+        ));
+        plan.facing = 0.0;
+        plan.outline.truncate(2);
+        assert_eq!(
+            get_grid_palaces(&plan),
+            Err(FloorPlanError::InvalidOutline)
+        );
+    }
+}
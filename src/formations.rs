@@ -0,0 +1,461 @@
+//! In 玄空飞星風水 (Xuan-Kong Fei-Xing Feng-Shui),
+//! some of 下卦図 (Xia-Gua-Tu) are given special
+//! names depending on where 九星 (Jiu-Xing) come to.
+//! These are called "formations", and this module
+//! detects them for the given `NatalChart`.
+//!
+//! [0] 旺山旺向 (Wang-Shan Wang-Xiang)
+//! [1] 上山下水 (Shang-Shan Xia-Shui)
+//! [2] 雙星到向 (Shuang-Xing Dao-Xiang)
+//! [3] 雙星到山 (Shuang-Xing Dao-Shan)
+//! [4] 伏吟 (Fu-Yin)
+//! [5] 反吟 (Fan-Yin)
+//! [6] 合十 (He-Shi)
+//! [7] 父母三般卦 (Fu-Mu San-Ban-Gua)
+//! [8] 連珠三般卦 (Lian-Zhu San-Ban-Gua)
+
+use serde::{Deserialize, Serialize};
+
+use crate::jiuxing::{
+    NatalChart, StarGrid, XiaGuaTuKind,
+    LO_SHU_FLIGHT_PATH,
+};
+use crate::language::{
+    Language, LanguageData, LanguageTrait,
+    NameDataTrait,
+};
+use crate::utils::get_json;
+
+/// Kinds of formations. The order is the same as that
+/// of `FORMATIONS`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum FormationKind {
+    WangShanWangXiang,  // 旺山旺向
+    ShangShanXiaShui,   // 上山下水
+    ShuangXingDaoXiang, // 雙星到向
+    ShuangXingDaoShan,  // 雙星到山
+    FuYin,              // 伏吟
+    FanYin,             // 反吟
+    HeShi,              // 合十
+    FuMuSanBanGua,      // 父母三般卦
+    LianZhuSanBanGua,   // 連珠三般卦
+}
+
+/// A struct representing a name of the formation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormationName {
+    pub id: u8,
+    pub name: Language,
+}
+
+/// A temporary struct for loading JSON data when
+/// defining a static const `FORMATIONS`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormationRawData {
+    pub id: u8,
+    pub name: LanguageData,
+}
+
+/// A struct representing the formation detected.
+/// `charts` are the charts involved (ex. 山星
+/// (Shan-Xing) for 伏吟 (Fu-Yin) of 山星 (Shan-Xing)),
+/// and `palaces` are directions of the palaces where
+/// the formation occurs ("" being the center).
+/// `whole_chart` is `true` when the formation occurs
+/// for all the palaces (ex. 全盤合十).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Formation {
+    pub kind: FormationKind,
+    pub name: Language,
+    pub charts: Vec<XiaGuaTuKind>,
+    pub palaces: Vec<String>,
+    pub whole_chart: bool,
+}
+
+impl LanguageTrait for FormationName {
+    fn name(&self) -> Box<Language> {
+        Box::new(self.name.clone())
+    }
+}
+
+impl NameDataTrait for FormationRawData {
+    fn name(&self) -> Box<LanguageData> {
+        Box::new(self.name.clone())
+    }
+}
+
+impl LanguageTrait for Formation {
+    fn name(&self) -> Box<Language> {
+        Box::new(self.name.clone())
+    }
+}
+
+lazy_static! {
+    /// A static vector with 9 items, each represents
+    /// a name of the formation.
+    ///
+    /// For attributes details stored in the vector is
+    /// found in JSON file:
+    /// `src/json/formations.json`
+    pub static ref FORMATIONS: Vec<FormationName> = {
+        let json = &include_str!("../json/formations.json");
+        let data: Vec<FormationRawData> =
+            get_json::<FormationRawData>(json);
+        data.iter()
+            .map(|item| FormationName {
+                id: item.id,
+                name: item.language_from_data(),
+            })
+            .collect()
+    };
+}
+
+impl Formation {
+    fn new(
+        kind: FormationKind,
+        charts: Vec<XiaGuaTuKind>,
+        palaces: Vec<&str>,
+    ) -> Self {
+        Formation {
+            kind,
+            name: FORMATIONS[kind as usize]
+                .name
+                .clone(),
+            charts,
+            whole_chart: palaces.len() == 9,
+            palaces: palaces
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// 九星 (Jiu-Xing) number (from 1 to 9) for the given
+/// direction.
+fn num(grid: &StarGrid, direction: &str) -> usize {
+    grid.get(direction).map_or(0, |index| index + 1)
+}
+
+/// Directions of the palaces which satisfy the given
+/// condition (in the order of `LO_SHU_FLIGHT_PATH`).
+fn palaces_where(
+    f: impl Fn(&str) -> bool,
+) -> Vec<&'static str> {
+    LO_SHU_FLIGHT_PATH
+        .iter()
+        .filter(|dir| f(dir))
+        .copied()
+        .collect()
+}
+
+/// 伏吟 (Fu-Yin) is when 五黄土星 (5 Yellow) comes
+/// to the center and flies in normal order, so that
+/// every 九星 (Jiu-Xing) returns to its original
+/// palace. 反吟 (Fan-Yin) is when it flies in reverse
+/// order, and every 九星 (Jiu-Xing) goes to the
+/// opposite palace.
+fn is_fu_yin(grid: &StarGrid) -> bool {
+    grid.center() == 4 && grid.get("nw") == Some(5)
+}
+
+fn is_fan_yin(grid: &StarGrid) -> bool {
+    grid.center() == 4 && grid.get("nw") == Some(3)
+}
+
+/// 父母三般卦 (Fu-Mu San-Ban-Gua) is when 3 stars in
+/// every palace are either 1-4-7, 2-5-8, or 3-6-9.
+fn is_fu_mu(nums: [usize; 3]) -> bool {
+    let [a, b, c] = nums;
+    a != b
+        && b != c
+        && a != c
+        && a % 3 == b % 3
+        && b % 3 == c % 3
+}
+
+/// 連珠三般卦 (Lian-Zhu San-Ban-Gua) is when 3 stars
+/// in every palace are consecutive (ex. 1-2-3, or
+/// 8-9-1 where 9 is followed by 1).
+fn is_lian_zhu(nums: [usize; 3]) -> bool {
+    (1..=9).any(|start| {
+        let set: Vec<usize> = (0..3)
+            .map(|i| (start + i - 1) % 9 + 1)
+            .collect();
+        nums.iter().all(|n| set.contains(n))
+            && nums[0] != nums[1]
+            && nums[1] != nums[2]
+            && nums[0] != nums[2]
+    })
+}
+
+/// Detects formations for the given `NatalChart`.
+/// Formations are returned in the order of
+/// `FORMATIONS`.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::formations::{get_formations, Formation, FormationKind};
+/// use mikaboshi::jiuxing::get_natal_chart;
+///
+/// // 八運 (Period 8), 丑山未向
+/// let chart = get_natal_chart(7, "sw", 1, "n").unwrap();
+/// let formations: Vec<Formation> = get_formations(&chart);
+/// assert_eq!(formations[0].kind, FormationKind::WangShanWangXiang);
+/// ```
+pub fn get_formations(
+    chart: &NatalChart,
+) -> Vec<Formation> {
+    use FormationKind::*;
+    use XiaGuaTuKind::*;
+
+    let period: usize = num(&chart.period, "");
+    let sitting: &str =
+        chart.sitting_direction.as_str();
+    let facing: &str =
+        chart.facing_direction.as_str();
+
+    // Where 旺星 (the star of the current period)
+    // comes to.
+    let shan_at_sitting: bool =
+        num(&chart.mountain, sitting) == period;
+    let shan_at_facing: bool =
+        num(&chart.mountain, facing) == period;
+    let xiang_at_sitting: bool =
+        num(&chart.facing, sitting) == period;
+    let xiang_at_facing: bool =
+        num(&chart.facing, facing) == period;
+
+    let mut formations: Vec<Formation> = vec![];
+
+    if shan_at_sitting && xiang_at_facing {
+        formations.push(Formation::new(
+            WangShanWangXiang,
+            vec![ShanXing, XiangXing],
+            vec![sitting, facing],
+        ));
+    }
+    if shan_at_facing && xiang_at_sitting {
+        formations.push(Formation::new(
+            ShangShanXiaShui,
+            vec![ShanXing, XiangXing],
+            vec![facing, sitting],
+        ));
+    }
+    if shan_at_facing && xiang_at_facing {
+        formations.push(Formation::new(
+            ShuangXingDaoXiang,
+            vec![ShanXing, XiangXing],
+            vec![facing],
+        ));
+    }
+    if shan_at_sitting && xiang_at_sitting {
+        formations.push(Formation::new(
+            ShuangXingDaoShan,
+            vec![ShanXing, XiangXing],
+            vec![sitting],
+        ));
+    }
+
+    for (kind, is) in [
+        (FuYin, is_fu_yin as fn(&StarGrid) -> bool),
+        (FanYin, is_fan_yin),
+    ] {
+        for (chart_kind, grid) in [
+            (ShanXing, &chart.mountain),
+            (XiangXing, &chart.facing),
+        ] {
+            if is(grid) {
+                formations.push(Formation::new(
+                    kind,
+                    vec![chart_kind],
+                    LO_SHU_FLIGHT_PATH.to_vec(),
+                ));
+            }
+        }
+    }
+
+    // 合十 (He-Shi) is when 2 stars in the palace add
+    // up to 10.
+    for (a_kind, a, b_kind, b) in [
+        (
+            UnPanXing,
+            &chart.period,
+            ShanXing,
+            &chart.mountain,
+        ),
+        (
+            UnPanXing,
+            &chart.period,
+            XiangXing,
+            &chart.facing,
+        ),
+        (
+            ShanXing,
+            &chart.mountain,
+            XiangXing,
+            &chart.facing,
+        ),
+    ] {
+        let palaces = palaces_where(|dir| {
+            num(a, dir) + num(b, dir) == 10
+        });
+        if !palaces.is_empty() {
+            formations.push(Formation::new(
+                HeShi,
+                vec![a_kind, b_kind],
+                palaces,
+            ));
+        }
+    }
+
+    let triples: Vec<[usize; 3]> = LO_SHU_FLIGHT_PATH
+        .iter()
+        .map(|dir| {
+            [
+                num(&chart.period, dir),
+                num(&chart.mountain, dir),
+                num(&chart.facing, dir),
+            ]
+        })
+        .collect();
+
+    for (kind, is) in [
+        (
+            FuMuSanBanGua,
+            is_fu_mu as fn([usize; 3]) -> bool,
+        ),
+        (LianZhuSanBanGua, is_lian_zhu),
+    ] {
+        if triples.iter().all(|nums| is(*nums)) {
+            formations.push(Formation::new(
+                kind,
+                vec![UnPanXing, ShanXing, XiangXing],
+                LO_SHU_FLIGHT_PATH.to_vec(),
+            ));
+        }
+    }
+
+    formations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jiuxing::get_natal_chart;
+
+    fn kinds(
+        chart: &NatalChart,
+    ) -> Vec<FormationKind> {
+        get_formations(chart)
+            .iter()
+            .map(|f| f.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_constant_formations() {
+        assert_eq!(FORMATIONS.len(), 9);
+        assert_eq!(
+            FORMATIONS[FormationKind::HeShi as usize]
+                .alphabet(),
+            "合十"
+        );
+    }
+
+    #[test]
+    fn test_wang_shan_wang_xiang() {
+        // 八運 (Period 8), 丑山未向
+        let chart =
+            get_natal_chart(7, "sw", 1, "n").unwrap();
+        let formations = get_formations(&chart);
+        assert_eq!(
+            formations[0].kind,
+            FormationKind::WangShanWangXiang
+        );
+        assert_eq!(
+            formations[0].palaces,
+            ["ne", "sw"]
+        );
+
+        // The facing chart has 五黄土星 (5 Yellow)
+        // flying in reverse order.
+        let fan_yin = formations
+            .iter()
+            .find(|f| f.kind == FormationKind::FanYin)
+            .unwrap();
+        assert_eq!(
+            fan_yin.charts,
+            [XiaGuaTuKind::XiangXing]
+        );
+        assert!(fan_yin.whole_chart);
+
+        // 運盤 (Un-Pan) and 山星 (Shan-Xing) add up to
+        // 10 in every palace.
+        let he_shi = formations
+            .iter()
+            .find(|f| f.kind == FormationKind::HeShi)
+            .unwrap();
+        assert_eq!(
+            he_shi.charts,
+            [
+                XiaGuaTuKind::UnPanXing,
+                XiaGuaTuKind::ShanXing
+            ]
+        );
+        assert!(he_shi.whole_chart);
+    }
+
+    #[test]
+    fn test_shang_shan_xia_shui() {
+        // 八運 (Period 8), 艮山坤向
+        let chart =
+            get_natal_chart(7, "sw", 2, "n").unwrap();
+        let kinds = kinds(&chart);
+        assert!(kinds.contains(
+            &FormationKind::ShangShanXiaShui
+        ));
+        assert!(kinds.contains(&FormationKind::FuYin));
+        assert!(!kinds.contains(
+            &FormationKind::WangShanWangXiang
+        ));
+    }
+
+    #[test]
+    fn test_shuang_xing() {
+        // 八運 (Period 8), 子山午向
+        let kinds_s = kinds(
+            &get_natal_chart(7, "s", 2, "n").unwrap(),
+        );
+        assert!(kinds_s.contains(
+            &FormationKind::ShuangXingDaoXiang
+        ));
+        // 八運 (Period 8), 午山子向
+        let kinds_n = kinds(
+            &get_natal_chart(7, "n", 2, "n").unwrap(),
+        );
+        assert!(kinds_n.contains(
+            &FormationKind::ShuangXingDaoShan
+        ));
+    }
+
+    #[test]
+    fn test_san_ban_gua() {
+        assert!(is_fu_mu([1, 4, 7]));
+        assert!(is_fu_mu([8, 2, 5]));
+        assert!(!is_fu_mu([1, 4, 4]));
+        assert!(!is_fu_mu([1, 2, 3]));
+        assert!(is_lian_zhu([3, 1, 2]));
+        assert!(is_lian_zhu([9, 1, 8]));
+        assert!(!is_lian_zhu([1, 2, 4]));
+        assert!(!is_lian_zhu([1, 1, 2]));
+    }
+}
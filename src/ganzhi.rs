@@ -37,30 +37,34 @@
 //! [10] 戌 (Xu)  
 //! [11] 亥 (Hai)  
 
-use chrono::naive::{NaiveDate, NaiveTime};
+use chrono::naive::{
+    NaiveDate, NaiveDateTime, NaiveTime,
+};
 use chrono::offset::{FixedOffset, Utc};
 use chrono::{DateTime, Datelike, Timelike};
 use serde::{Deserialize, Serialize};
 
 use sowngwala::time::{
-    julian_day_from_generic_date,
-    julian_day_from_generic_datetime,
     modified_julian_day_from_generic_datetime,
-    naive_date_from_generic_datetime,
     naive_time_from_generic_datetime, utc_from_fixed,
 };
 
+use crate::ephemeris::SunLongitudeProvider;
+
 use crate::language::{
     Language, LanguageData, LanguageTrait,
     NameDataTrait,
 };
 
-use crate::solar_terms::get_lichun;
-
-use crate::utils::{
-    get_json, longitude_of_the_sun_from_generic_date,
+use crate::solar_terms::{
+    get_instant_of_sun_longitude,
+    get_instant_of_sun_longitude_with,
+    get_last_term_instant,
+    get_last_term_instant_with, year_from_lichun,
 };
 
+use crate::utils::get_json;
+
 /// A struct representing 干 (Gan) or "Stem" and stores
 /// its attributes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -216,44 +220,90 @@ impl<'a> Bazi<'a> {
     pub fn from_fixed(
         fixed: DateTime<FixedOffset>,
     ) -> Bazi<'a> {
-        let utc = utc_from_fixed(fixed);
-        let year = get_year_ganzhi(utc);
-        let month = get_month_ganzhi(
-            Box::new(utc),
-            year.stem.num,
-        );
-        let day = get_day_ganzhi(Box::new(utc));
-        let hour = get_hour_ganzhi(
-            Box::new(
-                naive_time_from_generic_datetime(
-                    fixed,
-                ),
-            ),
-            day.stem.num,
-        );
+        Bazi::from_utc(
+            utc_from_fixed(fixed),
+            naive_time_from_generic_datetime(fixed),
+        )
+    }
 
-        Bazi::new(year, month, day, hour)
+    /// The same as `from_fixed`, but calculates the
+    /// position of the sun using the given provider.
+    pub fn from_fixed_with(
+        provider: &dyn SunLongitudeProvider,
+        fixed: DateTime<FixedOffset>,
+    ) -> Bazi<'a> {
+        Bazi::from_utc_with(
+            provider,
+            utc_from_fixed(fixed),
+            naive_time_from_generic_datetime(fixed),
+        )
     }
 
+    /// Instants of 立春 (Li-Chun) and other solar terms
+    /// are looked up from the precomputed table (see
+    /// `solar_terms::get_term_instants`).
     pub fn from_utc(
         utc: DateTime<Utc>,
         t: NaiveTime,
     ) -> Bazi<'a> {
-        let year = get_year_ganzhi(utc);
-        let month = get_month_ganzhi(
-            Box::new(utc),
-            year.stem.num,
-        );
-        let day = get_day_ganzhi(Box::new(utc));
-        let hour = get_hour_ganzhi(
-            Box::new(t),
-            day.stem.num,
-        );
+        let (lng, _) =
+            get_last_term_instant(utc.naive_utc());
+        bazi_from_lichun(
+            utc,
+            t,
+            get_instant_of_sun_longitude(
+                utc.year(),
+                315.0,
+            ),
+            lng,
+        )
+    }
 
-        Bazi::new(year, month, day, hour)
+    /// The same as `from_utc`, but calculates the
+    /// position of the sun using the given provider.
+    pub fn from_utc_with(
+        provider: &dyn SunLongitudeProvider,
+        utc: DateTime<Utc>,
+        t: NaiveTime,
+    ) -> Bazi<'a> {
+        let (lng, _) = get_last_term_instant_with(
+            provider,
+            utc.naive_utc(),
+        );
+        bazi_from_lichun(
+            utc,
+            t,
+            get_instant_of_sun_longitude_with(
+                provider,
+                utc.year(),
+                315.0,
+            ),
+            lng,
+        )
     }
 }
 
+/// `Bazi` for the given instant of 立春 (Li-Chun) and
+/// the longitude of the last solar term (which decides
+/// the month).
+fn bazi_from_lichun(
+    utc: DateTime<Utc>,
+    t: NaiveTime,
+    lichun: NaiveDateTime,
+    last_term_lng: f64,
+) -> Bazi<'static> {
+    let year = get_year_ganzhi(utc, lichun);
+    let month = get_month_ganzhi(
+        last_term_lng,
+        year.stem.num,
+    );
+    let day = get_day_ganzhi(Box::new(utc));
+    let hour =
+        get_hour_ganzhi(Box::new(t), day.stem.num);
+
+    Bazi::new(year, month, day, hour)
+}
+
 lazy_static! {
     /// A static vector with 60 items. `Vec<usize, usize>`
     /// where the first `usize` being the `STEMS` index,
@@ -386,25 +436,59 @@ lazy_static! {
     ];
 }
 
+/// 藏干 (Cang-Gan), or "Hidden Stems" for each 支
+/// (Zhi). Values are `STEMS` indexes beginning with
+/// 本氣 (Ben-Qi), followed by 中氣 (Zhong-Qi) and 餘氣
+/// (Yu-Qi) when exist.
+pub const HIDDEN_STEMS: [&[usize]; 12] = [
+    &[9],       // 子: 癸
+    &[5, 9, 7], // 丑: 己癸辛
+    &[0, 2, 4], // 寅: 甲丙戊
+    &[1],       // 卯: 乙
+    &[4, 1, 9], // 辰: 戊乙癸
+    &[2, 6, 4], // 巳: 丙庚戊
+    &[3, 5],    // 午: 丁己
+    &[5, 3, 1], // 未: 己丁乙
+    &[6, 8, 4], // 申: 庚壬戊
+    &[7],       // 酉: 辛
+    &[4, 7, 3], // 戌: 戊辛丁
+    &[8, 0],    // 亥: 壬甲
+];
+
+impl Branch {
+    /// Returns 藏干 (Cang-Gan), or "Hidden Stems" for
+    /// the branch (see `HIDDEN_STEMS`).
+    ///
+    /// Example:
+    /// ```rust
+    /// use mikaboshi::ganzhi::{Stem, BRANCHES};
+    /// use mikaboshi::language::LanguageTrait;
+    ///
+    /// // 寅 (Yin)
+    /// let stems: Vec<&Stem> = BRANCHES[2].hidden_stems();
+    /// let names: Vec<String> = stems.iter().map(|stem| stem.alphabet()).collect();
+    /// assert_eq!(names, vec!["甲", "丙", "戊"]);
+    /// ```
+    pub fn hidden_stems(&self) -> Vec<&'static Stem> {
+        HIDDEN_STEMS[(self.num - 1) as usize]
+            .iter()
+            .map(|&index| &STEMS[index])
+            .collect()
+    }
+}
+
 /// Year Ganzhi
 fn get_year_ganzhi(
     utc: DateTime<Utc>,
+    lichun: NaiveDateTime,
 ) -> GanZhi<'static> {
     // Year Stem and Branch are easily found.
     // However, we must watch out if it is before
     // or after Lichun. The year begins from Lichun,
     // and it belongs to last year if the date
     // is before Lichun.
-    let lichun: NaiveDate = get_lichun(utc.year());
-
     let year: i32 =
-        if julian_day_from_generic_datetime(utc)
-            < julian_day_from_generic_date(lichun)
-        {
-            utc.year() - 1
-        } else {
-            utc.year()
-        };
+        year_from_lichun(utc.naive_utc(), lichun);
 
     // Stem is found from the last digit of the year.
     // 0   1   2   3   4   5   6   7   8  9
@@ -428,18 +512,10 @@ fn get_year_ganzhi(
 }
 
 /// Month Ganzhi
-#[allow(clippy::boxed_local)]
 fn get_month_ganzhi(
-    utc: Box<DateTime<Utc>>,
+    lng: f64,
     year_stem_num: u8,
 ) -> GanZhi<'static> {
-    let utc = *utc;
-
-    let lng: f64 =
-        longitude_of_the_sun_from_generic_date(
-            naive_date_from_generic_datetime(utc),
-        );
-
     // Branch is easily found by looking at the
     // longitude of the sun.
     let branch_index: usize =
@@ -522,6 +598,29 @@ fn get_day_ganzhi(
     }
 }
 
+/// Returns Day Ganzhi for the given date.
+///
+/// Example:
+/// ```rust
+/// use chrono::naive::NaiveDate;
+/// use mikaboshi::ganzhi::{get_day_ganzhi_from_date, GanZhi};
+///
+/// let day: GanZhi = get_day_ganzhi_from_date(
+///     NaiveDate::from_ymd(2021, 7, 6),
+/// );
+/// assert_eq!(day.stem.num, 2); // 乙 (Yi)
+/// ```
+pub fn get_day_ganzhi_from_date(
+    date: NaiveDate,
+) -> GanZhi<'static> {
+    get_day_ganzhi(Box::new(
+        DateTime::<Utc>::from_utc(
+            date.and_hms(0, 0, 0),
+            Utc,
+        ),
+    ))
+}
+
 /// Hour Ganzhi
 #[allow(clippy::boxed_local)]
 fn get_hour_ganzhi(
@@ -586,6 +685,7 @@ fn get_hour_ganzhi(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ephemeris::DEFAULT_PROVIDER;
     use sowngwala::time::{build_fixed, build_utc};
 
     // TODO: GANZHI_SEXAGESIMAL
@@ -602,6 +702,25 @@ mod tests {
 
     // TODO: HOUR_STEM_TABLE
 
+    #[test]
+    fn test_hidden_stems() {
+        for (branch, stems) in
+            BRANCHES.iter().zip(HIDDEN_STEMS.iter())
+        {
+            assert!(!stems.is_empty());
+            assert_eq!(
+                branch.hidden_stems().len(),
+                stems.len()
+            );
+        }
+        let stems: Vec<String> = BRANCHES[10]
+            .hidden_stems()
+            .iter()
+            .map(|stem| stem.alphabet())
+            .collect();
+        assert_eq!(stems, vec!["戊", "辛", "丁"]);
+    }
+
     #[test]
     fn test_bazi_from_fixed() {
         let nanosecond: u32 = 137_790_000;
@@ -648,6 +767,63 @@ mod tests {
         assert_eq!(hour.alphabet(), "癸未");
     }
 
+    #[test]
+    fn test_bazi_at_lichun() {
+        // 立春 (Li-Chun) in 2022 is 20:51 (UT) on
+        // February 3rd. Both year and month change
+        // at the very moment.
+        let t = NaiveTime::from_hms(0, 0, 0);
+
+        let before: Bazi = Bazi::from_utc(
+            build_utc(2022, 2, 3, 19, 0, 0, 0),
+            t,
+        );
+        assert_eq!(before.year.alphabet(), "辛丑");
+        assert_eq!(before.month.alphabet(), "辛丑");
+
+        let after: Bazi = Bazi::from_utc(
+            build_utc(2022, 2, 3, 23, 0, 0, 0),
+            t,
+        );
+        assert_eq!(after.year.alphabet(), "壬寅");
+        assert_eq!(after.month.alphabet(), "壬寅");
+    }
+
+    #[test]
+    fn test_bazi_from_utc_with_default_provider() {
+        // 啓蟄 (Jingzhe) in 2022 is 14:44 (UT) on
+        // March 5th, and the month changes to 癸卯.
+        let t = NaiveTime::from_hms(0, 0, 0);
+        for utc in [
+            build_utc(2022, 3, 5, 14, 0, 0, 0),
+            build_utc(2022, 3, 5, 15, 30, 0, 0),
+        ] {
+            let cached: Bazi = Bazi::from_utc(utc, t);
+            let computed: Bazi = Bazi::from_utc_with(
+                &DEFAULT_PROVIDER,
+                utc,
+                t,
+            );
+            assert_eq!(
+                cached.month.alphabet(),
+                computed.month.alphabet()
+            );
+        }
+        let after: Bazi = Bazi::from_utc(
+            build_utc(2022, 3, 5, 15, 30, 0, 0),
+            t,
+        );
+        assert_eq!(after.month.alphabet(), "癸卯");
+    }
+
+    #[test]
+    fn test_get_day_ganzhi_from_date() {
+        let day: GanZhi = get_day_ganzhi_from_date(
+            NaiveDate::from_ymd(2021, 7, 6),
+        );
+        assert_eq!(day.alphabet(), "乙卯");
+    }
+
     #[test]
     fn test_bazi_from_utc() {
         let nanosecond: u32 = 275_570_000;
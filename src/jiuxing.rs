@@ -64,22 +64,40 @@
 //! [7] 八白土星 (8 White)  
 //! [8] 九紫火星 (9 Purple)  
 
-use chrono::naive::NaiveDate;
-use chrono::Datelike;
+use chrono::naive::{NaiveDate, NaiveDateTime};
+use chrono::{Datelike, Duration, Timelike};
 use serde::{Deserialize, Serialize};
-use sowngwala::time::julian_day_from_generic_date;
+use sowngwala::time::add_date;
 use std::collections::HashMap;
 use std::convert::TryInto;
+use std::fmt;
 
 use crate::compass::{
-    get_opposite_direction, DIRECTIONS,
-    DIRECTION_POSITIONS_IN_CHART,
+    get_opposite_direction,
+    get_twentyfour_direction_from_degrees,
+    get_twentyfour_index_from_direction, Direction,
+    DIRECTIONS, DIRECTION_POSITIONS_IN_CHART,
+    TWENTYFOUR_INDEX_TO_DIRECTIONS,
+};
+use crate::ephemeris::SunLongitudeProvider;
+use crate::ganzhi::{
+    get_day_ganzhi_from_date, GANZHI_SEXAGESIMAL,
 };
 use crate::language::{
     Language, LanguageData, LanguageTrait,
     NameDataTrait,
 };
 use crate::planet::{Planet, PLANETS};
+use crate::sanyuan::Period;
+use crate::solar_terms::{
+    get_date_of_sun_longitude,
+    get_date_of_sun_longitude_with,
+    get_instant_of_sun_longitude,
+    get_instant_of_sun_longitude_with,
+    get_last_term_instant,
+    get_last_term_instant_with, get_lichun,
+    get_lichun_with, year_from_lichun,
+};
 use crate::utils::{get_json, make_positive};
 use crate::wuxing::{WuXing, WU_XING};
 
@@ -121,7 +139,13 @@ impl NameDataTrait for JiuXingRawData {
 }
 
 #[derive(
-    Debug, Clone, Serialize, Deserialize, Copy,
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+    Copy,
+    PartialEq,
+    Eq,
 )]
 pub enum XiaGuaTuKind {
     UnPanXing, // 運盤
@@ -139,6 +163,20 @@ pub struct XiaGuaTu<'a> {
     pub chart: Option<[usize; 9]>,
 }
 
+/// A struct representing a chart for 紫白 (Zi-Bai),
+/// which are 九星 (Jiu-Xing) ruling the given time
+/// (such as 年紫白 (Nian Zi-Bai) for a year).
+/// `center` is the index of 九星 (Jiu-Xing) residing
+/// in the center, and `chart` is the result of
+/// flying it over 地盤 (Di-Pan) for `direction`
+/// (see `fly_flying_stars`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZiBaiChart {
+    pub center: usize,
+    pub direction: String,
+    pub chart: [usize; 9],
+}
+
 lazy_static! {
     /// A static vector with 9 items, each represents
     /// 九星 (Jiu-Xing).
@@ -384,20 +422,414 @@ pub fn normalize_jiuxing(index: i32) -> usize {
 pub fn unpan_xing_index(
     current: NaiveDate,
     lichun: NaiveDate,
+) -> usize {
+    // Both are dates, and are compared at midnight.
+    Period::from_year(year_from_lichun(
+        current.and_hms(0, 0, 0),
+        lichun.and_hms(0, 0, 0),
+    ))
+    .index()
+}
+
+/// The same as `unpan_xing_index`, but finds 立春
+/// (Li-Chun) for the year by itself.
+pub fn get_unpan_xing_index(
+    current: NaiveDate,
+) -> usize {
+    unpan_xing_index(
+        current,
+        get_lichun(current.year()),
+    )
+}
+
+/// The same as `get_unpan_xing_index`, but calculates
+/// the position of the sun using the given provider.
+pub fn get_unpan_xing_index_with(
+    provider: &dyn SunLongitudeProvider,
+    current: NaiveDate,
+) -> usize {
+    unpan_xing_index(
+        current,
+        get_lichun_with(provider, current.year()),
+    )
+}
+
+/// Flies 九星 (Jiu-Xing) in normal order for the
+/// given center over 地盤 (Di-Pan) for the given
+/// direction. Returns `None` when the direction is
+/// not one of `DIRECTIONS`.
+fn zibai_chart(
+    center: usize,
+    direction: &str,
+) -> Option<ZiBaiChart> {
+    let order: &[usize; 9] =
+        get_jiuxing_dipan_positions_from_direction(
+            direction,
+        )?;
+    Some(ZiBaiChart {
+        center,
+        direction: direction.to_string(),
+        chart: fly_flying_stars(center, order, false),
+    })
+}
+
+/// 年紫白 (Nian Zi-Bai) is 九星 (Jiu-Xing) ruling the
+/// year. Returns the index of 九星 (Jiu-Xing) which
+/// resides in the center for the given year (which
+/// begins from 立春 (Li-Chun)). Every year, the star
+/// in the center descends by one. 1864 being 一白水星
+/// (1 White), 2024 is 三碧木星 (3 Jade).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::jiuxing::annual_star;
+///
+/// // [2] 三碧木星 (3 Jade)
+/// assert_eq!(annual_star(2024), 2);
+/// ```
+pub fn annual_star(year: i32) -> usize {
+    (SAN_YUAN_JIU_YUN_START_YEAR as i32 - year)
+        .rem_euclid(9) as usize
+}
+
+/// The same as `annual_star`, but takes a date, and
+/// finds 立春 (Li-Chun) for the year by itself.
+pub fn get_annual_star(current: NaiveDate) -> usize {
+    annual_star(year_from_lichun(
+        current.and_hms(0, 0, 0),
+        get_instant_of_sun_longitude(
+            current.year(),
+            315.0,
+        ),
+    ))
+}
+
+/// The same as `get_annual_star`, but calculates
+/// the position of the sun using the given provider.
+pub fn get_annual_star_with(
+    provider: &dyn SunLongitudeProvider,
+    current: NaiveDate,
+) -> usize {
+    annual_star(year_from_lichun(
+        current.and_hms(0, 0, 0),
+        get_instant_of_sun_longitude_with(
+            provider,
+            current.year(),
+            315.0,
+        ),
+    ))
+}
+
+/// Returns the chart for 年紫白 (Nian Zi-Bai) for the
+/// given year. 九星 (Jiu-Xing) for the year flies in
+/// normal order over 地盤 (Di-Pan) for the given
+/// `direction` (see `JIU_XING_DI_PAN_POSITIONS`).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::jiuxing::{annual_chart, ZiBaiChart};
+///
+/// let chart: ZiBaiChart = annual_chart(2024, "n").unwrap();
+/// assert_eq!(chart.chart, [3, 7, 5, 4, 2, 0, 8, 6, 1]);
+/// ```
+pub fn annual_chart(
+    year: i32,
+    direction: &str,
+) -> Option<ZiBaiChart> {
+    zibai_chart(annual_star(year), direction)
+}
+
+/// 月紫白 (Yue Zi-Bai) is found from the year branch
+/// and the month. Months here are not Gregorian, but
+/// those of 節 (Jie) terms where the first month (寅)
+/// begins from 立春 (Li-Chun). For the first month,
+/// years of 子午卯酉 have 八白土星 (8 White), years of
+/// 辰戌丑未 have 五黄土星 (5 Yellow), and years of
+/// 寅申巳亥 have 二黒土星 (2 Black), and the star
+/// descends by one every month.
+fn monthly_star_from_instants(
+    datetime: NaiveDateTime,
+    lichun: NaiveDateTime,
+    last_term: f64,
 ) -> usize {
     let year: i32 =
-        if (julian_day_from_generic_date(current)
-            - julian_day_from_generic_date(lichun))
-            < 0_f64
-        {
-            current.year() - 1
-        } else {
-            current.year()
-        };
-    let dt: i32 =
-        year - SAN_YUAN_JIU_YUN_START_YEAR as i32;
-    let norm: i32 = dt % 180;
-    (norm / 20) as usize
+        year_from_lichun(datetime, lichun);
+
+    // 0   1   2   3   4   5   6   7   8  9   10  11
+    // 子  丑  寅  卯  辰  巳  午  未  申  酉  戌  亥
+    let branch: i32 = (year + 8).rem_euclid(12);
+
+    // [7] 八白 for 子午卯酉
+    // [4] 五黄 for 辰戌丑未
+    // [1] 二黒 for 寅申巳亥
+    let first: i32 = 7 - 3 * (branch % 3);
+
+    // 0 for 寅 (the month of 立春), 1 for 卯, ...
+    let month: i32 = ((last_term - 315.0)
+        .rem_euclid(360.0)
+        / 30.0)
+        .floor() as i32;
+
+    (first - month).rem_euclid(9) as usize
+}
+
+/// Returns the index of 九星 (Jiu-Xing) for 月紫白
+/// (Yue Zi-Bai) which resides in the center for the
+/// given date and time (UT). Boundaries of months are
+/// looked up from the solar term table (see
+/// `solar_terms::get_term_instants`).
+///
+/// Example:
+/// ```rust
+/// use chrono::naive::NaiveDate;
+/// use mikaboshi::jiuxing::monthly_star;
+///
+/// // [4] 五黄土星 (5 Yellow) for the first month of 2024
+/// let datetime = NaiveDate::from_ymd(2024, 2, 10).and_hms(0, 0, 0);
+/// assert_eq!(monthly_star(datetime), 4);
+/// ```
+pub fn monthly_star(
+    datetime: NaiveDateTime,
+) -> usize {
+    monthly_star_from_instants(
+        datetime,
+        get_instant_of_sun_longitude(
+            datetime.year(),
+            315.0,
+        ),
+        get_last_term_instant(datetime).0,
+    )
+}
+
+/// The same as `monthly_star`, but calculates the
+/// position of the sun using the given provider.
+pub fn monthly_star_with(
+    provider: &dyn SunLongitudeProvider,
+    datetime: NaiveDateTime,
+) -> usize {
+    monthly_star_from_instants(
+        datetime,
+        get_instant_of_sun_longitude_with(
+            provider,
+            datetime.year(),
+            315.0,
+        ),
+        get_last_term_instant_with(
+            provider, datetime,
+        )
+        .0,
+    )
+}
+
+/// Returns the chart for 月紫白 (Yue Zi-Bai) for the
+/// given date and time (UT). 九星 (Jiu-Xing) for the
+/// month flies in normal order over 地盤 (Di-Pan) for
+/// the given `direction`.
+pub fn monthly_chart(
+    datetime: NaiveDateTime,
+    direction: &str,
+) -> Option<ZiBaiChart> {
+    zibai_chart(monthly_star(datetime), direction)
+}
+
+/// 遁 (Dun) tells whether 九星 (Jiu-Xing) for days
+/// (and hours) are ascending or descending.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum Dun {
+    Yang, // 陽遁 (ascending)
+    Yin,  // 陰遁 (descending)
+}
+
+/// A struct representing the period of 遁 (Dun) for
+/// the given date. `anchor` is the 甲子 (Jia-Zi) day
+/// on which the period began.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DunPeriod {
+    pub dun: Dun,
+    pub anchor: NaiveDate,
+}
+
+/// For the given date of 冬至 (Dong-Zhi) or 夏至
+/// (Xia-Zhi), finds the nearest 甲子 (Jia-Zi) day.
+/// When it falls exactly in between two 甲子 (Jia-Zi)
+/// days, the earlier one is taken.
+fn nearest_jiazi(date: NaiveDate) -> NaiveDate {
+    let gz = get_day_ganzhi_from_date(date);
+    let index: i64 = GANZHI_SEXAGESIMAL
+        .iter()
+        .position(|&(stem, branch)| {
+            stem == (gz.stem.num - 1) as usize
+                && branch
+                    == (gz.branch.num - 1) as usize
+        })
+        .unwrap() as i64;
+    let diff: i64 = (-index).rem_euclid(60);
+    if diff < 30 {
+        add_date(date, diff)
+    } else {
+        add_date(date, diff - 60)
+    }
+}
+
+/// 陽遁 (Yang-Dun) begins on the 甲子 (Jia-Zi) day
+/// nearest to 冬至 (Dong-Zhi), and 陰遁 (Yin-Dun)
+/// begins on the 甲子 (Jia-Zi) day nearest to 夏至
+/// (Xia-Zhi). Given a function which returns the date
+/// of the sun reaching the given longitude for the
+/// year, finds the period the date belongs to.
+fn dun_period_from(
+    date: NaiveDate,
+    date_of_sun_longitude: &dyn Fn(
+        i32,
+        f64,
+    ) -> NaiveDate,
+) -> DunPeriod {
+    let year: i32 = date.year();
+    [
+        (Dun::Yin, year - 1, 90.0),
+        (Dun::Yang, year - 1, 270.0),
+        (Dun::Yin, year, 90.0),
+        (Dun::Yang, year, 270.0),
+    ]
+    .iter()
+    .map(|&(dun, y, angle)| DunPeriod {
+        dun,
+        anchor: nearest_jiazi(date_of_sun_longitude(
+            y, angle,
+        )),
+    })
+    .filter(|period| period.anchor <= date)
+    .max_by_key(|period| period.anchor)
+    .unwrap()
+}
+
+/// Returns the period of 遁 (Dun) for the given date.
+/// 陽遁 (Yang-Dun) begins on the 甲子 (Jia-Zi) day
+/// nearest to 冬至 (Dong-Zhi), and 陰遁 (Yin-Dun)
+/// begins on the 甲子 (Jia-Zi) day nearest to 夏至
+/// (Xia-Zhi).
+pub fn get_dun(date: NaiveDate) -> DunPeriod {
+    dun_period_from(date, &get_date_of_sun_longitude)
+}
+
+/// The same as `get_dun`, but calculates the position
+/// of the sun using the given provider.
+pub fn get_dun_with(
+    provider: &dyn SunLongitudeProvider,
+    date: NaiveDate,
+) -> DunPeriod {
+    dun_period_from(date, &|year, angle| {
+        get_date_of_sun_longitude_with(
+            provider, year, angle,
+        )
+    })
+}
+
+/// 日紫白 (Ri Zi-Bai) for 陽遁 (Yang-Dun) begins with
+/// 一白水星 (1 White) on the 甲子 (Jia-Zi) day, and
+/// ascends by one every day. For 陰遁 (Yin-Dun), it
+/// begins with 九紫火星 (9 Purple), and descends.
+fn daily_star_from_period(
+    date: NaiveDate,
+    period: &DunPeriod,
+) -> usize {
+    let days: i64 = (date - period.anchor).num_days();
+    match period.dun {
+        Dun::Yang => days.rem_euclid(9) as usize,
+        Dun::Yin => (8 - days).rem_euclid(9) as usize,
+    }
+}
+
+/// Returns the index of 九星 (Jiu-Xing) for 日紫白
+/// (Ri Zi-Bai) which resides in the center for the
+/// given date.
+///
+/// Example:
+/// ```rust
+/// use chrono::naive::NaiveDate;
+/// use mikaboshi::jiuxing::{daily_star, get_dun, Dun};
+///
+/// let date = NaiveDate::from_ymd(2024, 1, 1);
+/// let period = get_dun(date);
+/// assert_eq!(period.dun, Dun::Yang);
+/// let star: usize = daily_star(date);
+/// ```
+pub fn daily_star(date: NaiveDate) -> usize {
+    daily_star_from_period(date, &get_dun(date))
+}
+
+/// The same as `daily_star`, but calculates the
+/// position of the sun using the given provider.
+pub fn daily_star_with(
+    provider: &dyn SunLongitudeProvider,
+    date: NaiveDate,
+) -> usize {
+    daily_star_from_period(
+        date,
+        &get_dun_with(provider, date),
+    )
+}
+
+/// 時紫白 (Shi Zi-Bai) is found from the branch of the
+/// day and 遁 (Dun). For 陽遁 (Yang-Dun), 子 (Zi)
+/// hours of 子午卯酉 days have 一白水星 (1 White),
+/// those of 辰戌丑未 days have 四緑木星 (4 Green), and
+/// those of 寅申巳亥 days have 七赤金星 (7 Red), and
+/// ascends every 2 hours. For 陰遁 (Yin-Dun), they
+/// are 九紫火星 (9 Purple), 六白金星 (6 White), and
+/// 三碧木星 (3 Jade), and descends.
+/// Since 子 (Zi) hour begins at 23:00, the hour
+/// belongs to the next day.
+fn hourly_star_from(
+    datetime: NaiveDateTime,
+    get_period: &dyn Fn(NaiveDate) -> DunPeriod,
+) -> usize {
+    let shifted: NaiveDateTime =
+        datetime + Duration::hours(1);
+    let date: NaiveDate = shifted.date();
+    let hour: i32 = (shifted.hour() / 2) as i32;
+
+    // 0   1   2   3   4   5   6   7   8  9   10  11
+    // 子  丑  寅  卯  辰  巳  午  未  申  酉  戌  亥
+    let branch: i32 =
+        (get_day_ganzhi_from_date(date).branch.num
+            - 1) as i32;
+
+    match get_period(date).dun {
+        Dun::Yang => (3 * (branch % 3) + hour)
+            .rem_euclid(9)
+            as usize,
+        Dun::Yin => (8 - 3 * (branch % 3) - hour)
+            .rem_euclid(9)
+            as usize,
+    }
+}
+
+/// Returns the index of 九星 (Jiu-Xing) for 時紫白
+/// (Shi Zi-Bai) which resides in the center for the
+/// given date and time. 遁 (Dun) for hours is the same
+/// as that of the day.
+pub fn hourly_star(datetime: NaiveDateTime) -> usize {
+    hourly_star_from(datetime, &get_dun)
+}
+
+/// The same as `hourly_star`, but calculates the
+/// position of the sun using the given provider.
+pub fn hourly_star_with(
+    provider: &dyn SunLongitudeProvider,
+    datetime: NaiveDateTime,
+) -> usize {
+    hourly_star_from(datetime, &|date| {
+        get_dun_with(provider, date)
+    })
 }
 
 // /// Returns Jiu-xing (for Un-Pan)
@@ -419,20 +851,21 @@ pub fn unpan_xing_index(
 /// is always different since the position changes
 /// depending on which direction the device is
 /// pointing as the device rotates.
+/// See `fly_stars` for a validated version.
 pub fn fly_flying_stars(
     center: usize,
     order: &[usize; 9],
     reverse: bool,
 ) -> [usize; 9] {
-    let diff: usize = center - 4;
+    let diff: i32 = center as i32 - 4;
     order
         .iter()
         .map(|index: &usize| -> usize {
-            let index: usize = match reverse {
-                true => 8 - *index,
-                false => *index,
+            let index: i32 = match reverse {
+                true => 8 - *index as i32,
+                false => *index as i32,
             } + diff;
-            normalize_jiuxing(index as i32)
+            normalize_jiuxing(index)
         })
         .collect::<Vec<usize>>()
         .try_into()
@@ -448,15 +881,251 @@ pub fn fly_flying_stars(
 //         .unwrap()
 // }
 
+/// 飞泊 (Fei-Po) follows the path of 洛書 (Lo-Shu).
+/// Starting from the center, stars fly to "nw", "w",
+/// "ne", "s", "n", "sw", "e", and "se" ("" being
+/// the center).
+pub const LO_SHU_FLIGHT_PATH: [&str; 9] =
+    ["", "nw", "w", "ne", "s", "n", "sw", "e", "se"];
+
+/// Whether 九星 (Jiu-Xing) fly in normal order
+/// (順飛) or in reverse order (逆飛).
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum Flight {
+    Forward, // 順飛
+    Reverse, // 逆飛
+}
+
+/// Errors for 飞泊 (Fei-Po) and 下卦図 (Xia-Gua-Tu).
+#[derive(Debug, Clone, PartialEq)]
+pub enum JiuXingError {
+    /// 九星 (Jiu-Xing) index must be from 0 to 8.
+    InvalidStar(usize),
+    /// Direction must be one of `DIRECTIONS`.
+    InvalidDirection(String),
+    /// Sector must be from 1 to 3.
+    InvalidSector(usize),
+    /// The order does not match any of
+    /// `JIU_XING_DI_PAN_POSITIONS`.
+    InvalidOrder([usize; 9]),
+    /// Degrees must be a finite number.
+    InvalidDegrees(f32),
+}
+
+impl fmt::Display for JiuXingError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        match self {
+            JiuXingError::InvalidStar(index) => {
+                write!(
+                    f,
+                    "Invalid Jiu-Xing index: {}",
+                    index
+                )
+            }
+            JiuXingError::InvalidDirection(dir) => {
+                write!(
+                    f,
+                    "Invalid direction: {}",
+                    dir
+                )
+            }
+            JiuXingError::InvalidSector(sector) => {
+                write!(
+                    f,
+                    "Invalid sector: {}",
+                    sector
+                )
+            }
+            JiuXingError::InvalidOrder(order) => {
+                write!(
+                    f,
+                    "Invalid Di-Pan order: {:?}",
+                    order
+                )
+            }
+            JiuXingError::InvalidDegrees(degrees) => {
+                write!(
+                    f,
+                    "Invalid degrees: {}",
+                    degrees
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for JiuXingError {}
+
+/// A 3x3 grid of 九星 (Jiu-Xing) indexes. Cells are
+/// laid out in the same manner as
+/// `DIRECTION_POSITIONS_IN_CHART` for `direction`
+/// (which is the direction the device is pointing).
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct StarGrid {
+    pub direction: String,
+    pub cells: [[usize; 3]; 3],
+}
+
+impl StarGrid {
+    /// Creates a grid from a flat array (such as the
+    /// one returned from `fly_flying_stars`).
+    pub fn new(
+        direction: &str,
+        chart: &[usize; 9],
+    ) -> Result<StarGrid, JiuXingError> {
+        validate_direction(direction)?;
+        if let Some(index) =
+            chart.iter().find(|index| **index > 8)
+        {
+            return Err(JiuXingError::InvalidStar(
+                *index,
+            ));
+        }
+        let mut cells = [[0; 3]; 3];
+        for (i, index) in chart.iter().enumerate() {
+            cells[i / 3][i % 3] = *index;
+        }
+        Ok(StarGrid {
+            direction: direction.to_string(),
+            cells,
+        })
+    }
+
+    /// Returns 九星 (Jiu-Xing) index for the given
+    /// compass direction ("" being the center).
+    pub fn get(
+        &self,
+        direction: &str,
+    ) -> Option<usize> {
+        DIRECTION_POSITIONS_IN_CHART
+            .get(self.direction.as_str())?
+            .iter()
+            .position(|d| *d == direction)
+            .map(|i| self.cells[i / 3][i % 3])
+    }
+
+    /// Returns 九星 (Jiu-Xing) index in the center.
+    pub fn center(&self) -> usize {
+        self.cells[1][1]
+    }
+
+    /// Returns the grid as a flat array (compatible
+    /// with `fly_flying_stars`).
+    pub fn to_array(&self) -> [usize; 9] {
+        let mut chart = [0; 9];
+        for (i, index) in chart.iter_mut().enumerate()
+        {
+            *index = self.cells[i / 3][i % 3];
+        }
+        chart
+    }
+}
+
+fn validate_star(
+    index: usize,
+) -> Result<(), JiuXingError> {
+    match index {
+        0..=8 => Ok(()),
+        _ => Err(JiuXingError::InvalidStar(index)),
+    }
+}
+
+fn validate_direction(
+    direction: &str,
+) -> Result<(), JiuXingError> {
+    match DIRECTIONS.contains(&direction) {
+        true => Ok(()),
+        _ => Err(JiuXingError::InvalidDirection(
+            direction.to_string(),
+        )),
+    }
+}
+
+fn validate_sector(
+    sector: usize,
+) -> Result<(), JiuXingError> {
+    match sector {
+        1..=3 => Ok(()),
+        _ => Err(JiuXingError::InvalidSector(sector)),
+    }
+}
+
+/// A validated version of `fly_flying_stars`. Given
+/// 九星 (Jiu-Xing) index for the center, flies it
+/// along `LO_SHU_FLIGHT_PATH`, and lays out the result
+/// on `StarGrid` for `direction` (which is the
+/// direction the device is pointing).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::jiuxing::{fly_stars, Flight, StarGrid};
+///
+/// // [8] 九紫火星 (9 Purple) in the center.
+/// let grid: StarGrid = fly_stars(8, "n", Flight::Forward).unwrap();
+/// assert_eq!(grid.get("nw"), Some(0));
+/// ```
+pub fn fly_stars(
+    center: usize,
+    direction: &str,
+    flight: Flight,
+) -> Result<StarGrid, JiuXingError> {
+    validate_star(center)?;
+    validate_direction(direction)?;
+
+    let mut chart = [0; 9];
+    for (i, dir) in DIRECTION_POSITIONS_IN_CHART
+        [direction]
+        .iter()
+        .enumerate()
+    {
+        let step: i32 = LO_SHU_FLIGHT_PATH
+            .iter()
+            .position(|d| d == dir)
+            .ok_or_else(|| {
+                JiuXingError::InvalidDirection(
+                    dir.to_string(),
+                )
+            })? as i32;
+        chart[i] = match flight {
+            Flight::Forward => center as i32 + step,
+            Flight::Reverse => center as i32 - step,
+        }
+        .rem_euclid(9) as usize;
+    }
+    StarGrid::new(direction, &chart)
+}
+
 /// This is a useful well known formula for finding out
 /// whether 山星 (Shan-Xing) or 向星 (Xiang-Xing) is
-/// flying in normal order.
+/// flying in normal order. For 九星 (Jiu-Xing) with
+/// odd numbers, only 地元 (sector 1) is 陽 (Yang).
+/// For even numbers, 天元 (sector 2) and 人元
+/// (sector 3) are 陽 (Yang). 陽 (Yang) flies in normal
+/// order.
 ///
 /// IMPORTANT:
 /// It does not work when 九星 (Jiu-Xing) is "5".
 /// If such the case, center index for Un-Pan must
 /// be fed.
-fn is_shan_xiang_flying_normal(
+pub(crate) fn is_shan_xiang_flying_normal(
     index: usize,
     sector: usize,
 ) -> bool {
@@ -470,17 +1139,509 @@ fn is_shan_xiang_flying_normal(
 /// current direction facing.
 fn direction_from_dipan_order(
     order: &[usize; 9],
-) -> &'static str {
-    JIU_XING_DI_PAN_POSITIONS
-        .iter()
-        .find_map(|(dir, dipan_order)| {
-            match order.iter().eq(dipan_order.iter())
-            {
-                true => Some(*dir),
-                _ => None,
-            }
-        })
-        .unwrap_or("")
+) -> Option<&'static str> {
+    JIU_XING_DI_PAN_POSITIONS.iter().find_map(
+        |(dir, dipan_order)| match order
+            .iter()
+            .eq(dipan_order.iter())
+        {
+            true => Some(*dir),
+            _ => None,
+        },
+    )
+}
+
+/// A struct representing 下卦図 (Xia-Gua-Tu) for
+/// a building (which is sometimes called a "natal"
+/// chart). `period` is the chart for 運盤星 (Un-Pan
+/// Xing), `mountain` for 山星 (Shan-Xing), and
+/// `facing` for 向星 (Xiang-Xing). All the charts are
+/// laid out for the same direction (the direction the
+/// device is pointing).
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct NatalChart {
+    pub period: StarGrid,
+    pub mountain: StarGrid,
+    pub facing: StarGrid,
+    pub sitting_direction: String,
+    pub facing_direction: String,
+    pub sector: usize,
+}
+
+impl NatalChart {
+    /// For the given compass direction ("" being the
+    /// center), returns 九星 (Jiu-Xing) indexes for
+    /// 運盤星 (Un-Pan Xing), 山星 (Shan-Xing), and
+    /// 向星 (Xiang-Xing).
+    pub fn palace(
+        &self,
+        direction: &str,
+    ) -> Option<(usize, usize, usize)> {
+        Some((
+            self.period.get(direction)?,
+            self.mountain.get(direction)?,
+            self.facing.get(direction)?,
+        ))
+    }
+
+    /// Returns palaces for the center and 8 directions
+    /// (in the order of `LO_SHU_FLIGHT_PATH`) each
+    /// with its direction.
+    pub fn palaces(
+        &self,
+    ) -> Vec<(&'static str, (usize, usize, usize))>
+    {
+        LO_SHU_FLIGHT_PATH
+            .iter()
+            .filter_map(|dir| {
+                self.palace(dir).map(|p| (*dir, p))
+            })
+            .collect()
+    }
+}
+
+/// 替星 (Ti-Xing) for 二十四山向 (Er-Shi-Si
+/// Shan-Xiang) in the order of
+/// `TWENTYFOUR_INDEX_TO_DIRECTIONS` (which begins
+/// with 子 (Zi)). When calculating 替卦 (Ti-Gua),
+/// instead of 九星 (Jiu-Xing) found in 運盤 (Un-Pan),
+/// the corresponding 替星 (Ti-Xing) is flown.
+///
+/// 子癸甲申 --> [0] 一白 (貪狼)
+/// 壬卯乙未坤 --> [1] 二黒 (巨門)
+/// 乾亥辰巽巳戌 --> [5] 六白 (武曲)
+/// 酉辛丑艮丙 --> [6] 七赤 (破軍)
+/// 寅午庚丁 --> [8] 九紫 (右弼)
+pub const TI_XING: [usize; 24] = [
+    0, // 0: 子
+    0, // 1: 癸
+    6, // 2: 丑
+    6, // 3: 艮
+    8, // 4: 寅
+    0, // 5: 甲
+    1, // 6: 卯
+    1, // 7: 乙
+    5, // 8: 辰
+    5, // 9: 巽
+    5, // 10: 巳
+    6, // 11: 丙
+    8, // 12: 午
+    8, // 13: 丁
+    1, // 14: 未
+    1, // 15: 坤
+    0, // 16: 申
+    8, // 17: 庚
+    6, // 18: 酉
+    6, // 19: 辛
+    5, // 20: 戌
+    5, // 21: 乾
+    5, // 22: 亥
+    1, // 23: 壬
+];
+
+/// Degrees used by default for `get_jian_xiang`.
+pub const JIAN_XIANG_THRESHOLD: f32 = 3.0;
+
+/// For the given 九星 (Jiu-Xing) index, finds its
+/// original palace (in 洛書 (Lo-Shu) order), and
+/// returns 替星 (Ti-Xing) for the mountain of the
+/// given sector in the palace.
+fn ti_xing_from_index(
+    index: usize,
+    sector: usize,
+) -> usize {
+    match JIU_XING[index].direction.as_str() {
+        "" => index,
+        dir => {
+            TI_XING
+                [get_twentyfour_index_from_direction(
+                    dir, sector,
+                )]
+        }
+    }
+}
+
+/// 兼向 (Jian-Xiang) is when the facing is close to
+/// the border of the neighboring mountain in 二十四山向
+/// (Er-Shi-Si Shan-Xiang). When the given degrees are
+/// within `threshold` degrees from the border, returns
+/// the neighboring mountain the facing leans to.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::compass::Direction;
+/// use mikaboshi::jiuxing::{get_jian_xiang, JIAN_XIANG_THRESHOLD};
+///
+/// // 未 (sw1) leaning to 坤 (sw2)
+/// assert_eq!(
+///     get_jian_xiang(216.0, JIAN_XIANG_THRESHOLD),
+///     Some(Direction::new("sw", 2))
+/// );
+/// assert_eq!(get_jian_xiang(210.0, JIAN_XIANG_THRESHOLD), None);
+/// ```
+pub fn get_jian_xiang(
+    degrees: f32,
+    threshold: f32,
+) -> Option<Direction> {
+    if !degrees.is_finite() {
+        return None;
+    }
+    let degrees: f32 = degrees.rem_euclid(360.0);
+    let dir: Direction =
+        get_twentyfour_direction_from_degrees(
+            degrees,
+        );
+    let index: usize =
+        get_twentyfour_index_from_direction(
+            &dir.direction,
+            dir.sector,
+        );
+    // Degrees from the border on the left.
+    let offset: f32 =
+        (degrees - 7.5).rem_euclid(15.0);
+    if offset <= threshold {
+        Some(
+            TWENTYFOUR_INDEX_TO_DIRECTIONS
+                [(index + 23) % 24]
+                .clone(),
+        )
+    } else if 15.0 - offset <= threshold {
+        Some(
+            TWENTYFOUR_INDEX_TO_DIRECTIONS
+                [(index + 1) % 24]
+                .clone(),
+        )
+    } else {
+        None
+    }
+}
+
+/// Calculates 下卦図 (Xia-Gua-Tu) as `NatalChart`.
+/// Given 運盤星 (Un-Pan Xing) index for the center
+/// (`unpan_xing_center`), direction and sector for
+/// the facing (`xiang_xing_direction` and
+/// `xiang_xing_sector`), flies 運盤星 (Un-Pan Xing),
+/// 山星 (Shan-Xing), and 向星 (Xiang-Xing), and lays
+/// them out for `layout_direction` (which is the
+/// direction the device is pointing, and is usually
+/// "n").
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::jiuxing::{get_natal_chart, NatalChart};
+///
+/// // 八運 (Period 8), 丑山未向 (facing "sw1")
+/// let chart: NatalChart = get_natal_chart(7, "sw", 1, "n").unwrap();
+/// let (period, mountain, facing) = chart.palace("sw").unwrap();
+/// ```
+pub fn get_natal_chart(
+    unpan_xing_center: usize,
+    xiang_xing_direction: &str,
+    xiang_xing_sector: usize,
+    layout_direction: &str,
+) -> Result<NatalChart, JiuXingError> {
+    fly_natal_chart(
+        unpan_xing_center,
+        xiang_xing_direction,
+        xiang_xing_sector,
+        layout_direction,
+        false,
+    )
+}
+
+/// The same as `get_natal_chart`, but calculates
+/// 替卦 (Ti-Gua) instead of 下卦 (Xia-Gua). For 山星
+/// (Shan-Xing) and 向星 (Xiang-Xing), 九星 (Jiu-Xing)
+/// to fly from the center are replaced with 替星
+/// (Ti-Xing) (see `TI_XING`), while they fly in the
+/// same order as they would for 下卦 (Xia-Gua).
+/// 替卦 (Ti-Gua) is used when the facing is 兼向
+/// (Jian-Xiang) (see `get_jian_xiang`).
+pub fn get_tigua_natal_chart(
+    unpan_xing_center: usize,
+    xiang_xing_direction: &str,
+    xiang_xing_sector: usize,
+    layout_direction: &str,
+) -> Result<NatalChart, JiuXingError> {
+    fly_natal_chart(
+        unpan_xing_center,
+        xiang_xing_direction,
+        xiang_xing_sector,
+        layout_direction,
+        true,
+    )
+}
+
+fn fly_natal_chart(
+    unpan_xing_center: usize,
+    xiang_xing_direction: &str,
+    xiang_xing_sector: usize,
+    layout_direction: &str,
+    tigua: bool,
+) -> Result<NatalChart, JiuXingError> {
+    validate_star(unpan_xing_center)?;
+    validate_direction(xiang_xing_direction)?;
+    validate_sector(xiang_xing_sector)?;
+    validate_direction(layout_direction)?;
+
+    // `chart` for 運盤星 (Un-Pan Xing) is straight
+    // forward. The center 九星 (Jiu-Xing) is already
+    // given, and you simply have to fly the given
+    // chart.
+    let period: StarGrid = fly_stars(
+        unpan_xing_center,
+        layout_direction,
+        Flight::Forward,
+    )?;
+
+    // `direction` for 山星 (Shan-Xing) is just the
+    // opposite of 向星 (Xiang-Xing).
+    let sitting_direction: &str =
+        get_opposite_direction(xiang_xing_direction);
+
+    let fly = |dir: &str| -> Result<StarGrid, JiuXingError> {
+        // Since we only know directions for 山星
+        // (Shan-Xing) and 向星 (Xiang-Xing), we will
+        // look into the Un-Pan chart, and will find
+        // out what 九星 (Jiu-Xing) we have for the
+        // direction.
+        let center: usize = period.get(dir).ok_or_else(
+            || JiuXingError::InvalidDirection(dir.into()),
+        )?;
+
+        // Having 五黄土星 (5 Yellow) is a special
+        // case, and the formula bellow does not work.
+        // Therefore, replacing it with 運盤星
+        // (Un-Pan Xing) index.
+        let original: usize = if center == 4 {
+            unpan_xing_center
+        } else {
+            center
+        };
+
+        // It is important to figure out whether it
+        // is flying in normal or reverse order.
+        // To do so, we will use a useful well known
+        // formula.
+        let normal: bool = is_shan_xiang_flying_normal(
+            original,
+            xiang_xing_sector,
+        );
+
+        // For 替卦 (Ti-Gua), the star to fly is
+        // replaced with 替星 (Ti-Xing).
+        let center: usize = if tigua {
+            ti_xing_from_index(original, xiang_xing_sector)
+        } else {
+            center
+        };
+
+        // Now, calculate for the flying chart.
+        fly_stars(
+            center,
+            layout_direction,
+            if normal {
+                Flight::Forward
+            } else {
+                Flight::Reverse
+            },
+        )
+    };
+
+    Ok(NatalChart {
+        mountain: fly(sitting_direction)?,
+        facing: fly(xiang_xing_direction)?,
+        period,
+        sitting_direction: sitting_direction
+            .to_string(),
+        facing_direction: xiang_xing_direction
+            .to_string(),
+        sector: xiang_xing_sector,
+    })
+}
+
+/// Describes how `NatalChart` was derived in
+/// `get_natal_chart_from_degrees`. `year` is the year
+/// which begins from `lichun`, and `unpan_xing_center`
+/// is 運盤星 (Un-Pan Xing) index for the year.
+/// `facing` and `sitting` are directions in 二十四山向
+/// (Er-Shi-Si Shan-Xiang), and `facing_index` and
+/// `sitting_index` are their indexes in
+/// `TWENTYFOUR_INDEX_TO_DIRECTIONS`. `jian_xiang` is
+/// the neighboring mountain when the facing is 兼向
+/// (Jian-Xiang) and the chart is 替卦 (Ti-Gua).
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize,
+)]
+pub struct NatalChartDerivation {
+    pub facing_degrees: f32,
+    pub facing: Direction,
+    pub facing_index: usize,
+    pub sitting: Direction,
+    pub sitting_index: usize,
+    pub lichun: NaiveDate,
+    pub year: i32,
+    pub unpan_xing_center: usize,
+    pub jian_xiang: Option<Direction>,
+}
+
+/// `NatalChart` with its derivation.
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize,
+)]
+pub struct DerivedNatalChart {
+    pub chart: NatalChart,
+    pub derivation: NatalChartDerivation,
+}
+
+fn derive_natal_chart(
+    facing_degrees: f32,
+    datetime: NaiveDateTime,
+    lichun: NaiveDate,
+    threshold: Option<f32>,
+) -> Result<DerivedNatalChart, JiuXingError> {
+    if !facing_degrees.is_finite() {
+        return Err(JiuXingError::InvalidDegrees(
+            facing_degrees,
+        ));
+    }
+    let facing_degrees: f32 =
+        facing_degrees.rem_euclid(360.0);
+    let facing: Direction =
+        get_twentyfour_direction_from_degrees(
+            facing_degrees,
+        );
+    let sitting = Direction::new(
+        get_opposite_direction(&facing.direction),
+        facing.sector,
+    );
+    let date: NaiveDate = datetime.date();
+    let unpan_xing_center: usize =
+        unpan_xing_index(date, lichun);
+    let jian_xiang: Option<Direction> = threshold
+        .and_then(|t| {
+            get_jian_xiang(facing_degrees, t)
+        });
+
+    Ok(DerivedNatalChart {
+        chart: fly_natal_chart(
+            unpan_xing_center,
+            &facing.direction,
+            facing.sector,
+            "n",
+            jian_xiang.is_some(),
+        )?,
+        derivation: NatalChartDerivation {
+            facing_degrees,
+            facing_index:
+                get_twentyfour_index_from_direction(
+                    &facing.direction,
+                    facing.sector,
+                ),
+            sitting_index:
+                get_twentyfour_index_from_direction(
+                    &sitting.direction,
+                    sitting.sector,
+                ),
+            facing,
+            sitting,
+            lichun,
+            year: year_from_lichun(
+                date.and_hms(0, 0, 0),
+                lichun.and_hms(0, 0, 0),
+            ),
+            unpan_xing_center,
+            jian_xiang,
+        },
+    })
+}
+
+/// Calculates 下卦図 (Xia-Gua-Tu) in one call. Given
+/// the bearing of the facing (in degrees) and the date
+/// and time of construction (or moving in), finds
+/// 運盤星 (Un-Pan Xing) for the year (which begins
+/// from 立春 (Li-Chun)), and the facing direction in
+/// 二十四山向 (Er-Shi-Si Shan-Xiang), and returns
+/// `NatalChart` (laid out for "n") together with
+/// `NatalChartDerivation`.
+///
+/// Example:
+/// ```rust
+/// use chrono::naive::NaiveDate;
+/// use mikaboshi::jiuxing::{get_natal_chart_from_degrees, DerivedNatalChart};
+///
+/// let datetime = NaiveDate::from_ymd(2010, 5, 1).and_hms(12, 0, 0);
+/// let derived: DerivedNatalChart =
+///     get_natal_chart_from_degrees(210.0, datetime).unwrap();
+/// assert_eq!(derived.derivation.facing.direction, "sw");
+/// ```
+pub fn get_natal_chart_from_degrees(
+    facing_degrees: f32,
+    datetime: NaiveDateTime,
+) -> Result<DerivedNatalChart, JiuXingError> {
+    derive_natal_chart(
+        facing_degrees,
+        datetime,
+        get_lichun(datetime.year()),
+        None,
+    )
+}
+
+/// The same as `get_natal_chart_from_degrees`, but
+/// calculates the position of the sun using the given
+/// provider.
+pub fn get_natal_chart_from_degrees_with(
+    provider: &dyn SunLongitudeProvider,
+    facing_degrees: f32,
+    datetime: NaiveDateTime,
+) -> Result<DerivedNatalChart, JiuXingError> {
+    derive_natal_chart(
+        facing_degrees,
+        datetime,
+        get_lichun_with(provider, datetime.year()),
+        None,
+    )
+}
+
+/// The same as `get_natal_chart_from_degrees`, but
+/// when the facing is 兼向 (Jian-Xiang) for the given
+/// `threshold` (in degrees; see `get_jian_xiang`),
+/// calculates 替卦 (Ti-Gua) instead of 下卦 (Xia-Gua).
+pub fn get_natal_chart_from_degrees_tigua(
+    facing_degrees: f32,
+    datetime: NaiveDateTime,
+    threshold: f32,
+) -> Result<DerivedNatalChart, JiuXingError> {
+    derive_natal_chart(
+        facing_degrees,
+        datetime,
+        get_lichun(datetime.year()),
+        Some(threshold),
+    )
+}
+
+/// The same as `get_natal_chart_from_degrees_tigua`,
+/// but calculates the position of the sun using the
+/// given provider.
+pub fn get_natal_chart_from_degrees_tigua_with(
+    provider: &dyn SunLongitudeProvider,
+    facing_degrees: f32,
+    datetime: NaiveDateTime,
+    threshold: f32,
+) -> Result<DerivedNatalChart, JiuXingError> {
+    derive_natal_chart(
+        facing_degrees,
+        datetime,
+        get_lichun_with(provider, datetime.year()),
+        Some(threshold),
+    )
 }
 
 /// Calculates for 下卦図 (Xia-Gua-Tu). 1st and 2nd
@@ -492,6 +1653,16 @@ fn direction_from_dipan_order(
 /// 3rd and 4th arguments (`xiang_xing_direction` and
 /// `xiang_xing_sector`.
 ///
+/// Returns `JiuXingError` when `unpan_xing_center` is
+/// not from 0 to 8, `unpan_xing_order` is not one of
+/// `JIU_XING_DI_PAN_POSITIONS`, `xiang_xing_direction`
+/// is not one of `DIRECTIONS`, or `xiang_xing_sector`
+/// is not from 1 to 3.
+///
+/// This is kept for the existing front-end which
+/// expects a `HashMap`. Consider using
+/// `get_natal_chart` instead.
+///
 /// Example:
 /// ```rust
 /// use std::collections::HashMap;
@@ -501,7 +1672,7 @@ fn direction_from_dipan_order(
 /// use wasm_bindgen::prelude::*;
 ///
 /// #[wasm_bindgen]
-/// pub fn xx(params: &JsValue) -> JsValue {
+/// pub fn xx(params: &JsValue) -> Result<JsValue, JsValue> {
 ///     let params: XiaGuaTuParams = params.into_serde().unwrap();
 ///     let unpan_xing_order: [usize; 9] =
 ///         params
@@ -515,8 +1686,9 @@ fn direction_from_dipan_order(
 ///         &unpan_xing_order,
 ///         params.xiang_xing_direction.as_str(),
 ///         params.xiang_xing_sector,
-///     );
-///     JsValue::from_serde(&xia_gua_tu).unwrap()
+///     )
+///     .map_err(|err| JsValue::from(err.to_string()))?;
+///     Ok(JsValue::from_serde(&xia_gua_tu).unwrap())
 /// }
 /// ```
 pub fn get_xiaguatu_from_unpan_index<'a>(
@@ -524,135 +1696,61 @@ pub fn get_xiaguatu_from_unpan_index<'a>(
     unpan_xing_order: &'a [usize; 9],
     xiang_xing_direction: &'a str,
     xiang_xing_sector: usize,
-) -> HashMap<&'a str, XiaGuaTu<'a>> {
+) -> Result<
+    HashMap<&'a str, XiaGuaTu<'a>>,
+    JiuXingError,
+> {
+    // First, we need to find out which direction
+    // the device is currently pointing to.
+    let curr_dir: &str =
+        direction_from_dipan_order(unpan_xing_order)
+            .ok_or(JiuXingError::InvalidOrder(
+                *unpan_xing_order,
+            ))?;
+
+    let natal: NatalChart = get_natal_chart(
+        unpan_xing_center,
+        xiang_xing_direction,
+        xiang_xing_sector,
+        curr_dir,
+    )?;
+
     let mut xgtu = HashMap::new();
 
-    // `chart` for 運盤星 (Un-Pan Xing) is straight
-    // forward. The center 九星 (Jiu-Xing) is already
-    // given, and you simply have to fly the given
-    // chart.
     xgtu.insert(
         "unpan_xing",
         XiaGuaTu {
             kind: XiaGuaTuKind::UnPanXing,
-            center: Some(unpan_xing_center),
+            center: Some(natal.period.center()),
             direction: None,
             sector: None,
-            chart: Some(fly_flying_stars(
-                unpan_xing_center,
-                unpan_xing_order,
-                false,
-            )),
+            chart: Some(natal.period.to_array()),
         },
     );
-
-    // `chart` for 山星 (Shan-Xing) to be later
-    // calculated. `direction` for 山星 (Shan-Xing)
-    // is just the opposite of 向星 (Xiang-Xing).
     xgtu.insert(
         "shan_xing",
         XiaGuaTu {
             kind: XiaGuaTuKind::ShanXing,
-            center: None,
+            center: Some(natal.mountain.center()),
             direction: Some(get_opposite_direction(
                 xiang_xing_direction,
             )),
             sector: Some(xiang_xing_sector),
-            chart: None,
+            chart: Some(natal.mountain.to_array()),
         },
     );
-
-    // `chart` for 向星 (Xiang-Xing) to be later
-    // calculated. `direction` is already given.
     xgtu.insert(
         "xiang_xing",
         XiaGuaTu {
             kind: XiaGuaTuKind::XiangXing,
-            center: None,
+            center: Some(natal.facing.center()),
             direction: Some(xiang_xing_direction),
             sector: Some(xiang_xing_sector),
-            chart: None,
+            chart: Some(natal.facing.to_array()),
         },
     );
 
-    // First, we need to find out which direction
-    // the device is currently pointing to.
-    let curr_dir: &str =
-        direction_from_dipan_order(unpan_xing_order);
-
-    // Once the direction is found, then we will
-    // obtain the compass direction mapping.
-    let compass_positions: [&str; 9] =
-        DIRECTION_POSITIONS_IN_CHART[curr_dir];
-
-    // Find `center` and `chart` for both 山星
-    // (Shan-Xing) and 向星 (Xiang-Xing).
-    for key in ["shan_xing", "xiang_xing"] {
-        // Since we only know directions for 山星
-        // (Shan-Xing) and 向星 (Xiang-Xing), we will
-        // look into the Un-Pan chart, and will find
-        // out what 九星 (Jiu-Xing) we have for the
-        // direction.
-
-        // Initially, we only know the direction.
-        let dir: &str =
-            xgtu.get(key).unwrap().direction.unwrap();
-
-        // For the direction, find out its array index.
-        let pos: usize = compass_positions
-            .iter()
-            .position(|&d| d == dir)
-            .unwrap();
-
-        // Once the index is found, then find out its
-        // 九星 (Jiu-Xing).
-        let center: usize = (xgtu
-            .get("unpan_xing")
-            .unwrap()
-            .chart
-            .unwrap())[pos];
-
-        let prev: &XiaGuaTu = xgtu.get(key).unwrap();
-
-        // It is important to figure out whether it
-        // is flying in normal or reverse order.
-        // To do so, we will use a useful well known
-        // formula.
-        let normal: bool =
-            is_shan_xiang_flying_normal(
-                // Having 五黄土星 (5 Yellow) is a
-                // special case, and the formula does
-                // not work. Therefore, replacing it
-                // with 運盤星 (Un-Pan Xing) index.
-                if center == 4 {
-                    xgtu.get("unpan_xing")
-                        .unwrap()
-                        .center
-                        .unwrap()
-                } else {
-                    center
-                },
-                xgtu.get(key)
-                    .unwrap()
-                    .sector
-                    .unwrap(),
-            );
-
-        // Now, calculate for the flying chart.
-        let chart: [usize; 9] = fly_flying_stars(
-            center,
-            unpan_xing_order,
-            normal,
-        );
-
-        *xgtu.get_mut(key).unwrap() = XiaGuaTu {
-            center: Some(center),
-            chart: Some(chart),
-            ..*prev
-        };
-    }
-
-    xgtu
+    Ok(xgtu)
 }
 
 #[cfg(test)]
@@ -716,11 +1814,633 @@ mod tests {
         assert_eq!(res, exp);
     }
 
+    #[test]
+    fn test_get_unpan_xing_index() {
+        // 8 White for 2004 to 2023
+        assert_eq!(
+            get_unpan_xing_index(
+                NaiveDate::from_ymd(2024, 2, 3)
+            ),
+            7
+        );
+        // 9 Purple for 2024 to 2043
+        assert_eq!(
+            get_unpan_xing_index(
+                NaiveDate::from_ymd(2024, 2, 6)
+            ),
+            8
+        );
+        // 9 Purple for 1844 to 1863
+        assert_eq!(
+            unpan_xing_index(
+                NaiveDate::from_ymd(1850, 6, 1),
+                NaiveDate::from_ymd(1850, 2, 4)
+            ),
+            8
+        );
+    }
+
+    #[test]
+    fn test_annual_star() {
+        // [0] 一白水星 (1 White)
+        assert_eq!(annual_star(1864), 0);
+        // [3] 四緑木星 (4 Green)
+        assert_eq!(annual_star(2023), 3);
+        // [2] 三碧木星 (3 Jade)
+        assert_eq!(annual_star(2024), 2);
+        // [1] 二黒土星 (2 Black)
+        assert_eq!(annual_star(2025), 1);
+        // Years before 1864.
+        assert_eq!(annual_star(1863), 1);
+        assert_eq!(annual_star(1855), 0);
+    }
+
+    #[test]
+    fn test_get_annual_star() {
+        // Before 立春 (Li-Chun), still 2023.
+        assert_eq!(
+            get_annual_star(NaiveDate::from_ymd(
+                2024, 2, 3
+            )),
+            3
+        );
+        assert_eq!(
+            get_annual_star(NaiveDate::from_ymd(
+                2024, 2, 5
+            )),
+            2
+        );
+    }
+
+    #[test]
+    fn test_annual_chart() {
+        // 2025 has 二黒土星 (2 Black) in the center.
+        //
+        // [2] 三碧 [6] 七赤 [4] 五黄
+        // [3] 四緑 [1] 二黒 [8] 九紫
+        // [7] 八白 [5] 六白 [0] 一白
+        let chart = annual_chart(2025, "n").unwrap();
+        assert_eq!(chart.center, 1);
+        assert_eq!(
+            chart.chart,
+            [2, 6, 4, 3, 1, 8, 7, 5, 0]
+        );
+        assert_eq!(
+            annual_chart(2024, "n").unwrap().chart,
+            [3, 7, 5, 4, 2, 0, 8, 6, 1]
+        );
+        assert!(annual_chart(2025, "x").is_none());
+    }
+
+    #[test]
+    fn test_monthly_star() {
+        let star = |y, m, d| {
+            monthly_star(
+                NaiveDate::from_ymd(y, m, d)
+                    .and_hms(12, 0, 0),
+            )
+        };
+        // 2023 (卯) begins with 八白土星 (8 White).
+        assert_eq!(star(2023, 2, 10), 7);
+        assert_eq!(star(2023, 3, 10), 6);
+        // January still belongs to 2023.
+        assert_eq!(star(2024, 1, 10), 5);
+        // 2024 (辰) begins with 五黄土星 (5 Yellow).
+        assert_eq!(star(2024, 2, 10), 4);
+        // 2025 (巳) begins with 二黒土星 (2 Black).
+        assert_eq!(star(2025, 2, 10), 1);
+        assert_eq!(star(2025, 12, 31), 0);
+    }
+
+    #[test]
+    fn test_monthly_chart() {
+        let datetime =
+            NaiveDate::from_ymd(2024, 2, 10)
+                .and_hms(0, 0, 0);
+        let chart =
+            monthly_chart(datetime, "n").unwrap();
+        assert_eq!(chart.center, 4);
+        assert_eq!(
+            chart.chart,
+            *get_jiuxing_dipan_positions_from_direction(
+                "n"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            monthly_star_with(
+                &crate::ephemeris::DEFAULT_PROVIDER,
+                datetime
+            ),
+            4
+        );
+    }
+
+    #[test]
+    fn test_get_dun() {
+        for (date, dun) in [
+            (
+                NaiveDate::from_ymd(2024, 1, 1),
+                Dun::Yang,
+            ),
+            (
+                NaiveDate::from_ymd(2024, 8, 1),
+                Dun::Yin,
+            ),
+            (
+                NaiveDate::from_ymd(1700, 1, 1),
+                Dun::Yang,
+            ),
+        ] {
+            let period = get_dun(date);
+            assert_eq!(period.dun, dun);
+            assert!(period.anchor <= date);
+            // The anchor is always 甲子 (Jia-Zi).
+            let gz = get_day_ganzhi_from_date(
+                period.anchor,
+            );
+            assert_eq!(
+                (gz.stem.num, gz.branch.num),
+                (1, 1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_daily_star() {
+        let yang =
+            get_dun(NaiveDate::from_ymd(2024, 1, 1));
+        assert_eq!(daily_star(yang.anchor), 0);
+        assert_eq!(
+            daily_star(add_date(yang.anchor, 1)),
+            1
+        );
+        assert_eq!(
+            daily_star(add_date(yang.anchor, 9)),
+            0
+        );
+
+        let yin =
+            get_dun(NaiveDate::from_ymd(2024, 8, 1));
+        assert_eq!(daily_star(yin.anchor), 8);
+        assert_eq!(
+            daily_star(add_date(yin.anchor, 1)),
+            7
+        );
+        // The day before 陰遁 (Yin-Dun) is still 陽遁
+        // (Yang-Dun).
+        assert_eq!(
+            get_dun(add_date(yin.anchor, -1)).dun,
+            Dun::Yang
+        );
+    }
+
+    #[test]
+    fn test_hourly_star() {
+        // 甲子 (Jia-Zi) day of 陽遁 (Yang-Dun)
+        let yang =
+            get_dun(NaiveDate::from_ymd(2024, 1, 1));
+        let at = |h, m| {
+            hourly_star(yang.anchor.and_hms(h, m, 0))
+        };
+        assert_eq!(at(0, 30), 0);
+        assert_eq!(at(1, 0), 1);
+        assert_eq!(at(22, 59), 2);
+        // 23:00 belongs to the next day (乙丑) which
+        // begins with 四緑木星 (4 Green).
+        assert_eq!(at(23, 0), 3);
+
+        let yin =
+            get_dun(NaiveDate::from_ymd(2024, 8, 1));
+        assert_eq!(
+            hourly_star(yin.anchor.and_hms(0, 0, 0)),
+            8
+        );
+        assert_eq!(
+            hourly_star(yin.anchor.and_hms(1, 0, 0)),
+            7
+        );
+    }
+
     // TODO: unpan_xing_index
     // TODO: unpan_xing_data <--- NOT IN USE
     // TODO: flying_stars_chart <--- NOT IN USE
-    // TODO: is_shan_xiang_flying_normal
-    // TODO: get_xiaguatu_from_unpan_index
+
+    #[test]
+    fn test_is_shan_xiang_flying_normal() {
+        // 一白水星 (1 White): 壬 (+), 子 (-), 癸 (-)
+        assert!(is_shan_xiang_flying_normal(0, 1));
+        assert!(!is_shan_xiang_flying_normal(0, 2));
+        assert!(!is_shan_xiang_flying_normal(0, 3));
+        // 二黒土星 (2 Black): 未 (-), 坤 (+), 申 (+)
+        assert!(!is_shan_xiang_flying_normal(1, 1));
+        assert!(is_shan_xiang_flying_normal(1, 2));
+        assert!(is_shan_xiang_flying_normal(1, 3));
+    }
+
+    #[test]
+    fn test_fly_stars_all() {
+        for center in 0..9 {
+            for dir in DIRECTIONS.iter() {
+                let order =
+                    JIU_XING_DI_PAN_POSITIONS[dir];
+                for (flight, reverse) in [
+                    (Flight::Forward, false),
+                    (Flight::Reverse, true),
+                ] {
+                    let grid = fly_stars(
+                        center, dir, flight,
+                    )
+                    .unwrap();
+                    assert_eq!(grid.center(), center);
+                    assert_eq!(
+                        grid.to_array(),
+                        fly_flying_stars(
+                            center, &order, reverse
+                        )
+                    );
+                    // Every star appears only once.
+                    let mut stars = grid.to_array();
+                    stars.sort_unstable();
+                    assert_eq!(
+                        stars,
+                        [0, 1, 2, 3, 4, 5, 6, 7, 8]
+                    );
+                    // The next star along the path.
+                    let next = match flight {
+                        Flight::Forward => {
+                            (center + 1) % 9
+                        }
+                        Flight::Reverse => {
+                            (center + 8) % 9
+                        }
+                    };
+                    assert_eq!(
+                        grid.get("nw"),
+                        Some(next)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fly_stars_invalid() {
+        assert_eq!(
+            fly_stars(9, "n", Flight::Forward),
+            Err(JiuXingError::InvalidStar(9))
+        );
+        assert_eq!(
+            fly_stars(0, "x", Flight::Forward),
+            Err(JiuXingError::InvalidDirection(
+                "x".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_star_grid() {
+        let grid = StarGrid::new(
+            "ne",
+            &JIU_XING_DI_PAN_POSITIONS["ne"],
+        )
+        .unwrap();
+        for dir in DIRECTIONS.iter() {
+            assert_eq!(
+                grid.get(dir),
+                Some(DIRECTION_TO_JIU_XING[dir])
+            );
+        }
+        assert_eq!(grid.get(""), Some(4));
+        assert_eq!(grid.get("x"), None);
+        assert!(StarGrid::new("n", &[9; 9]).is_err());
+    }
+
+    #[test]
+    fn test_get_xiaguatu_from_unpan_index_all() {
+        for center in 0..9 {
+            for curr_dir in DIRECTIONS.iter() {
+                let order = JIU_XING_DI_PAN_POSITIONS
+                    [curr_dir];
+                for dir in DIRECTIONS.iter() {
+                    for sector in 1..4 {
+                        let xgtu =
+                            get_xiaguatu_from_unpan_index(
+                                center, &order, dir, sector,
+                            )
+                            .unwrap();
+                        let unpan = StarGrid::new(
+                            curr_dir,
+                            &xgtu["unpan_xing"]
+                                .chart
+                                .unwrap(),
+                        )
+                        .unwrap();
+                        assert_eq!(
+                            unpan.center(),
+                            center
+                        );
+                        // 向星 (Xiang-Xing) in the center
+                        // is 運盤星 (Un-Pan Xing) at the
+                        // facing.
+                        let xiang =
+                            &xgtu["xiang_xing"];
+                        assert_eq!(
+                            xiang.center,
+                            unpan.get(dir)
+                        );
+                        let shan = &xgtu["shan_xing"];
+                        assert_eq!(
+                            shan.center,
+                            unpan.get(
+                                get_opposite_direction(
+                                    dir
+                                )
+                            )
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_xiaguatu_from_unpan_index_invalid() {
+        let order = JIU_XING_DI_PAN_POSITIONS["n"];
+        assert_eq!(
+            get_xiaguatu_from_unpan_index(
+                9, &order, "s", 2
+            )
+            .unwrap_err(),
+            JiuXingError::InvalidStar(9)
+        );
+        assert_eq!(
+            get_xiaguatu_from_unpan_index(
+                7, &order, "x", 2
+            )
+            .unwrap_err(),
+            JiuXingError::InvalidDirection(
+                "x".into()
+            )
+        );
+        assert_eq!(
+            get_xiaguatu_from_unpan_index(
+                7, &order, "s", 0
+            )
+            .unwrap_err(),
+            JiuXingError::InvalidSector(0)
+        );
+        let wrong = [0; 9];
+        assert_eq!(
+            get_xiaguatu_from_unpan_index(
+                7, &wrong, "s", 2
+            )
+            .unwrap_err(),
+            JiuXingError::InvalidOrder(wrong)
+        );
+    }
+
+    #[test]
+    fn test_get_natal_chart() {
+        let chart =
+            get_natal_chart(7, "sw", 1, "n").unwrap();
+        assert_eq!(chart.sitting_direction, "ne");
+        assert_eq!(chart.facing_direction, "sw");
+        // 八運 (Period 8) 丑山未向 is 旺山旺向.
+        //
+        //   運盤        山星        向星
+        // 9 4 2       1 6 8       4 9 2
+        // 1 8 6       9 2 4       3 5 7
+        // 5 3 7       5 7 3       8 1 6
+        assert_eq!(chart.palace(""), Some((7, 1, 4)));
+        assert_eq!(
+            chart.palace("ne"),
+            Some((1, 7, 1))
+        );
+        assert_eq!(
+            chart.palace("sw"),
+            Some((4, 4, 7))
+        );
+        assert_eq!(chart.palace("x"), None);
+        assert_eq!(chart.palaces().len(), 9);
+        assert_eq!(chart.palaces()[0].0, "");
+
+        // Only the layout differs.
+        let rotated =
+            get_natal_chart(7, "sw", 1, "s").unwrap();
+        for dir in LO_SHU_FLIGHT_PATH.iter() {
+            assert_eq!(
+                rotated.palace(dir),
+                chart.palace(dir)
+            );
+        }
+
+        // Serializable for the front-end.
+        let json =
+            serde_json::to_string(&chart).unwrap();
+        let back: NatalChart =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(back, chart);
+
+        assert!(
+            get_natal_chart(7, "sw", 1, "x").is_err()
+        );
+    }
+
+    #[test]
+    fn test_get_natal_chart_from_degrees() {
+        // 丑山未向 (facing "sw1") built in 2010.
+        let datetime =
+            NaiveDate::from_ymd(2010, 5, 1)
+                .and_hms(12, 0, 0);
+        let derived = get_natal_chart_from_degrees(
+            210.0, datetime,
+        )
+        .unwrap();
+        let derivation = &derived.derivation;
+        assert_eq!(
+            derivation.facing,
+            Direction::new("sw", 1)
+        );
+        assert_eq!(
+            derivation.sitting,
+            Direction::new("ne", 1)
+        );
+        assert_eq!(derivation.facing_index, 14);
+        assert_eq!(derivation.sitting_index, 2);
+        assert_eq!(derivation.year, 2010);
+        assert_eq!(derivation.unpan_xing_center, 7);
+        assert_eq!(
+            derived.chart,
+            get_natal_chart(7, "sw", 1, "n").unwrap()
+        );
+
+        // Before 立春 (Li-Chun) in 2024, it is still
+        // 八運 (Period 8).
+        let derived = get_natal_chart_from_degrees(
+            -150.0,
+            NaiveDate::from_ymd(2024, 2, 1)
+                .and_hms(0, 0, 0),
+        )
+        .unwrap();
+        assert_eq!(
+            derived.derivation.facing_degrees,
+            210.0
+        );
+        assert_eq!(derived.derivation.year, 2023);
+        assert_eq!(
+            derived.derivation.unpan_xing_center,
+            7
+        );
+
+        assert!(get_natal_chart_from_degrees(
+            f32::NAN,
+            datetime
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_constant_ti_xing() {
+        // 5 mountains for 巨門, 6 for 武曲, ...
+        for (index, count) in
+            [(0, 4), (1, 5), (5, 6), (6, 5), (8, 4)]
+        {
+            assert_eq!(
+                TI_XING
+                    .iter()
+                    .filter(|i| **i == index)
+                    .count(),
+                count
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_jian_xiang() {
+        let t = JIAN_XIANG_THRESHOLD;
+        assert_eq!(get_jian_xiang(210.0, t), None);
+        // 未 (sw1) leaning to 坤 (sw2) or 丁 (s3).
+        assert_eq!(
+            get_jian_xiang(216.0, t),
+            Some(Direction::new("sw", 2))
+        );
+        assert_eq!(
+            get_jian_xiang(203.0, t),
+            Some(Direction::new("s", 3))
+        );
+        // 子 (n2) leaning to 壬 (n1).
+        assert_eq!(
+            get_jian_xiang(-6.0, t),
+            Some(Direction::new("n", 1))
+        );
+        // Configurable threshold.
+        assert_eq!(get_jian_xiang(214.0, t), None);
+        assert_eq!(
+            get_jian_xiang(214.0, 4.5),
+            Some(Direction::new("sw", 2))
+        );
+    }
+
+    #[test]
+    fn test_get_tigua_natal_chart() {
+        // 八運 (Period 8) 子山午向
+        let xiagua =
+            get_natal_chart(7, "s", 2, "n").unwrap();
+        let tigua =
+            get_tigua_natal_chart(7, "s", 2, "n")
+                .unwrap();
+        assert_eq!(tigua.period, xiagua.period);
+        // 山星 (Shan-Xing): [3] 四緑 (巽) --> [5] 六白
+        assert_eq!(xiagua.mountain.center(), 3);
+        assert_eq!(tigua.mountain.center(), 5);
+        // 向星 (Xiang-Xing): [2] 三碧 (卯) --> [1] 二黒
+        assert_eq!(xiagua.facing.center(), 2);
+        assert_eq!(tigua.facing.center(), 1);
+        // They fly in the same order as 下卦 (Xia-Gua).
+        assert_eq!(tigua.mountain.get("nw"), Some(6));
+        assert_eq!(
+            xiagua.mountain.get("nw"),
+            Some(4)
+        );
+
+        // When 替星 (Ti-Xing) is the same, so is the
+        // chart. 八運 (Period 8) 丑山未向 for 山星
+        // (Shan-Xing): [1] 二黒 (未) --> [1] 二黒
+        assert_eq!(
+            get_tigua_natal_chart(7, "sw", 1, "n")
+                .unwrap()
+                .mountain,
+            get_natal_chart(7, "sw", 1, "n")
+                .unwrap()
+                .mountain
+        );
+    }
+
+    #[test]
+    fn test_get_natal_chart_from_degrees_tigua() {
+        let datetime =
+            NaiveDate::from_ymd(2010, 5, 1)
+                .and_hms(12, 0, 0);
+        let derived =
+            get_natal_chart_from_degrees_tigua(
+                181.0,
+                datetime,
+                JIAN_XIANG_THRESHOLD,
+            )
+            .unwrap();
+        assert_eq!(
+            derived.derivation.jian_xiang,
+            None
+        );
+        assert_eq!(
+            derived.chart,
+            get_natal_chart(7, "s", 2, "n").unwrap()
+        );
+
+        let derived =
+            get_natal_chart_from_degrees_tigua(
+                186.0,
+                datetime,
+                JIAN_XIANG_THRESHOLD,
+            )
+            .unwrap();
+        assert_eq!(
+            derived.derivation.jian_xiang,
+            Some(Direction::new("s", 3))
+        );
+        assert_eq!(
+            derived.chart,
+            get_tigua_natal_chart(7, "s", 2, "n")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_xiaguatu_period_8_chou_wei() {
+        // 丑山未向 (sitting "ne1", facing "sw1") for
+        // 八運 (Period 8) is known as 旺山旺向 where
+        // 八白土星 (8 White) comes to the mountain for
+        // 山星 (Shan-Xing), and to the facing for
+        // 向星 (Xiang-Xing).
+        let order = JIU_XING_DI_PAN_POSITIONS["n"];
+        let xgtu = get_xiaguatu_from_unpan_index(
+            7, &order, "sw", 1,
+        )
+        .unwrap();
+        let shan = StarGrid::new(
+            "n",
+            &xgtu["shan_xing"].chart.unwrap(),
+        )
+        .unwrap();
+        let xiang = StarGrid::new(
+            "n",
+            &xgtu["xiang_xing"].chart.unwrap(),
+        )
+        .unwrap();
+        assert_eq!(shan.get("ne"), Some(7));
+        assert_eq!(xiang.get("sw"), Some(7));
+    }
 
     // ===============================================
     // LO-SHU ORDER - Normal Order
@@ -0,0 +1,695 @@
+//! 九星気学 (Kyusei Kigaku), or "Nine Star Ki", is
+//! a Japanese school of fortune telling which uses
+//! 九星 (Jiu-Xing) for a person. For each person,
+//! there are:
+//!
+//! (1) 本命星 (Honmei-sei) --> 年紫白 (Nian Zi-Bai)
+//! for the year of birth (which begins from 立春
+//! (Li-Chun)).
+//! (2) 月命星 (Getsumei-sei) --> 月紫白 (Yue Zi-Bai)
+//! for the month of birth (which begins from the
+//! solar term for the month).
+//! (3) 傾斜宮 (Keisha-kyu) --> When 月命星
+//! (Getsumei-sei) is placed in the center and flown,
+//! the palace where 本命星 (Honmei-sei) comes to.
+//!
+//! When 本命星 (Honmei-sei) and 月命星 (Getsumei-sei)
+//! are the same (called 同会 (Dokai)), 本命星
+//! (Honmei-sei) comes to the center. If such the case,
+//! the palace opposite to the original palace of 本命星
+//! (Honmei-sei) is taken. For 五黄土星 (5 Yellow), it
+//! is 乾宮 (Ken-kyu) for men, and 兌宮 (Da-kyu) for
+//! women.
+//!
+//! For directions, 九星 (Jiu-Xing) for the year, the
+//! month, and the day are flown, and each direction is
+//! found either 吉方位 (Kippoi) or 凶方位 (Kyohoi) for
+//! the person. 凶方位 (Kyohoi) are:
+//!
+//! (1) 五黄殺 (Goou-satsu) --> Where 五黄土星 (5
+//! Yellow) comes to.
+//! (2) 暗剣殺 (Anken-satsu) --> Opposite to 五黄殺
+//! (Goou-satsu).
+//! (3) 歳破 (Sai-ha), 月破 (Getsu-ha), 日破 (Nichi-ha)
+//! --> Opposite to the branch of the year, the month,
+//! or the day.
+//! (4) 本命殺 (Honmei-satsu) --> Where 本命星
+//! (Honmei-sei) comes to.
+//! (5) 本命的殺 (Honmei-teki-satsu) --> Opposite to
+//! 本命殺 (Honmei-satsu).
+//!
+//! Of the rest, directions with stars compatible with
+//! 本命星 (Honmei-sei) by 五行 (Wu-Xing) (either 比和
+//! (Bi-He) or 相生 (Xiang-Sheng)) are 吉方位 (Kippoi).
+
+use chrono::naive::{NaiveDate, NaiveDateTime};
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+use crate::bazhai::Gender;
+use crate::compass::{
+    get_opposite_direction, DIRECTIONS,
+};
+use crate::ephemeris::SunLongitudeProvider;
+use crate::ganzhi::get_day_ganzhi_from_date;
+use crate::jiuxing::{
+    annual_star, daily_star, daily_star_with,
+    fly_stars, monthly_star, monthly_star_with,
+    Flight, JiuXingError, StarGrid, JIU_XING,
+    LO_SHU_FLIGHT_PATH,
+};
+use crate::language::LanguageDetails;
+use crate::solar_terms::{
+    get_instant_of_sun_longitude,
+    get_instant_of_sun_longitude_with,
+    get_last_term_instant,
+    get_last_term_instant_with, year_from_lichun,
+};
+use crate::wuxing::{
+    get_wuxing_index, get_wuxing_relation,
+    WuXingRelation,
+};
+
+/// A struct representing a star for 九星気学 (Kyusei
+/// Kigaku). `index` is 九星 (Jiu-Xing) index, and
+/// `name` is the Japanese name (from `JiuXing.name.ja`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KyuseiStar {
+    pub index: usize,
+    pub name: LanguageDetails,
+}
+
+impl KyuseiStar {
+    fn new(index: usize) -> Self {
+        KyuseiStar {
+            index,
+            name: JIU_XING[index].name.ja.clone(),
+        }
+    }
+}
+
+/// A struct representing natal stars for a person.
+/// `keisha` is 傾斜宮 (Keisha-kyu) given as 九星
+/// (Jiu-Xing) originally residing in the palace, and
+/// `keisha_direction` is the direction of the palace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Kyusei {
+    pub honmei: KyuseiStar,
+    pub getsumei: KyuseiStar,
+    pub keisha: KyuseiStar,
+    pub keisha_direction: String,
+}
+
+/// Returns 傾斜宮 (Keisha-kyu) as 九星 (Jiu-Xing)
+/// index for the given 本命星 (Honmei-sei) and 月命星
+/// (Getsumei-sei).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::bazhai::Gender;
+/// use mikaboshi::kyusei::keisha;
+///
+/// // 七赤金星 (7 Red) and 八白土星 (8 White)
+/// // --> 巽宮 (Son-kyu) for 四緑木星 (4 Green)
+/// assert_eq!(keisha(6, 7, Gender::Male), Some(3));
+/// ```
+pub fn keisha(
+    honmei: usize,
+    getsumei: usize,
+    gender: Gender,
+) -> Option<usize> {
+    let grid: StarGrid =
+        fly_stars(getsumei, "n", Flight::Forward)
+            .ok()?;
+    let dir: &str = LO_SHU_FLIGHT_PATH
+        .iter()
+        .find(|dir| grid.get(dir) == Some(honmei))?;
+
+    let dir: &str = match (dir, honmei, gender) {
+        ("", 4, Gender::Male) => "nw",
+        ("", 4, Gender::Female) => "w",
+        ("", _, _) => get_opposite_direction(
+            JIU_XING[honmei].direction.as_str(),
+        ),
+        (dir, _, _) => dir,
+    };
+
+    JIU_XING
+        .iter()
+        .position(|jiuxing| jiuxing.direction == dir)
+}
+
+/// Returns `Kyusei` for the given birth date and time
+/// (UT) and gender.
+///
+/// Example:
+/// ```rust
+/// use chrono::NaiveDate;
+/// use mikaboshi::bazhai::Gender;
+/// use mikaboshi::kyusei::{get_kyusei, Kyusei};
+///
+/// let birth = NaiveDate::from_ymd(1984, 3, 1).and_hms(12, 0, 0);
+/// let kyusei: Kyusei = get_kyusei(birth, Gender::Male);
+/// assert_eq!(kyusei.honmei.name.alphabet, "しちせききんせい");
+/// assert_eq!(kyusei.getsumei.index, 7);
+/// assert_eq!(kyusei.keisha_direction, "se");
+/// ```
+pub fn get_kyusei(
+    birth: NaiveDateTime,
+    gender: Gender,
+) -> Kyusei {
+    kyusei_from_stars(
+        honmei_from_lichun(
+            birth,
+            get_instant_of_sun_longitude(
+                birth.year(),
+                315.0,
+            ),
+        ),
+        monthly_star(birth),
+        gender,
+    )
+}
+
+/// The same as `get_kyusei`, but calculates the
+/// position of the sun using the given provider.
+pub fn get_kyusei_with(
+    provider: &dyn SunLongitudeProvider,
+    birth: NaiveDateTime,
+    gender: Gender,
+) -> Kyusei {
+    kyusei_from_stars(
+        honmei_from_lichun(
+            birth,
+            get_instant_of_sun_longitude_with(
+                provider,
+                birth.year(),
+                315.0,
+            ),
+        ),
+        monthly_star_with(provider, birth),
+        gender,
+    )
+}
+
+/// 本命星 (Honmei-sei) is 年紫白 (Nian Zi-Bai) for the
+/// year which begins from 立春 (Li-Chun).
+fn honmei_from_lichun(
+    birth: NaiveDateTime,
+    lichun: NaiveDateTime,
+) -> usize {
+    annual_star(year_from_lichun(birth, lichun))
+}
+
+fn kyusei_from_stars(
+    honmei: usize,
+    getsumei: usize,
+    gender: Gender,
+) -> Kyusei {
+    // Both are valid 九星 (Jiu-Xing) indexes, and
+    // there is always a palace for them.
+    let keisha: usize =
+        keisha(honmei, getsumei, gender).unwrap();
+    Kyusei {
+        honmei: KyuseiStar::new(honmei),
+        getsumei: KyuseiStar::new(getsumei),
+        keisha_direction: JIU_XING[keisha]
+            .direction
+            .clone(),
+        keisha: KyuseiStar::new(keisha),
+    }
+}
+
+/// Directions for 12 branches (0 for 子, 1 for 丑,
+/// ...).
+const BRANCH_DIRECTIONS: [&str; 12] = [
+    "n", "ne", "ne", "e", "se", "se", "s", "sw",
+    "sw", "w", "nw", "nw",
+];
+
+/// Whether the directions are for the year, the
+/// month, or the day.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum LuckPeriod {
+    Year,
+    Month,
+    Day,
+}
+
+/// Reasons for a direction being 吉方位 (Kippoi) or
+/// 凶方位 (Kyohoi).
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum LuckReason {
+    GoouSatsu,       // 五黄殺
+    AnkenSatsu,      // 暗剣殺
+    SaiHa,           // 歳破
+    GetsuHa,         // 月破
+    NichiHa,         // 日破
+    HonmeiSatsu,     // 本命殺
+    HonmeiTekiSatsu, // 本命的殺
+    Compatible,      // 相性の良い星
+    Incompatible,    // 相性の悪い星
+}
+
+/// A struct representing a direction for a person.
+/// `star` is 九星 (Jiu-Xing) index which comes to the
+/// direction, and `relation` is that of 本命星
+/// (Honmei-sei) to the star by 五行 (Wu-Xing).
+/// `auspicious` is `true` only when `reasons` has
+/// nothing but `LuckReason::Compatible`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectionalLuck {
+    pub direction: String,
+    pub star: usize,
+    pub relation: WuXingRelation,
+    pub reasons: Vec<LuckReason>,
+    pub auspicious: bool,
+}
+
+/// A struct representing 8 directions for a person
+/// for the year, the month, or the day. `center` is
+/// 九星 (Jiu-Xing) index in the center, and `branch`
+/// is the branch for the period (0 for 子, 1 for 丑,
+/// ...). `directions` are in the order of
+/// `compass::DIRECTIONS`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectionalLuckChart {
+    pub period: LuckPeriod,
+    pub center: usize,
+    pub branch: usize,
+    pub directions: Vec<DirectionalLuck>,
+}
+
+impl DirectionalLuckChart {
+    /// Returns `DirectionalLuck` for the given
+    /// direction.
+    pub fn get(
+        &self,
+        dir: &str,
+    ) -> Option<&DirectionalLuck> {
+        self.directions
+            .iter()
+            .find(|luck| luck.direction == dir)
+    }
+}
+
+/// 五行 (Wu-Xing) index for the given 九星 (Jiu-Xing).
+fn element(index: usize) -> usize {
+    // All 九星 (Jiu-Xing) have one of 五行 (Wu-Xing).
+    get_wuxing_index(&JIU_XING[index].element)
+        .unwrap()
+}
+
+/// The direction where the given star comes to
+/// (`None` when it is in the center).
+fn find_star(
+    grid: &StarGrid,
+    star: usize,
+) -> Option<&str> {
+    DIRECTIONS
+        .iter()
+        .find(|dir| grid.get(dir) == Some(star))
+        .copied()
+}
+
+/// Returns `DirectionalLuckChart` for 本命星
+/// (Honmei-sei) of `honmei` when `center` (九星
+/// (Jiu-Xing) index) is in the center, and `branch`
+/// (0 for 子, 1 for 丑, ...) is the branch for the
+/// given `period`.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::kyusei::{directional_luck, LuckPeriod, LuckReason};
+///
+/// // 一白水星 (1 White) in 2026 (丙午) which has
+/// // 一白水星 (1 White) in the center.
+/// let chart = directional_luck(0, 0, 6, LuckPeriod::Year).unwrap();
+/// let n = chart.get("n").unwrap();
+/// assert_eq!(n.reasons, vec![LuckReason::AnkenSatsu, LuckReason::SaiHa]);
+/// // 七赤金星 (7 Red) generates 一白水星 (1 White).
+/// assert_eq!(chart.get("sw").unwrap().star, 6);
+/// assert!(chart.get("sw").unwrap().auspicious);
+/// // 九紫火星 (9 Purple) is overcome by 一白水星 (1 White).
+/// assert!(!chart.get("se").unwrap().auspicious);
+/// ```
+pub fn directional_luck(
+    honmei: usize,
+    center: usize,
+    branch: usize,
+    period: LuckPeriod,
+) -> Result<DirectionalLuckChart, JiuXingError> {
+    if honmei > 8 {
+        return Err(JiuXingError::InvalidStar(
+            honmei,
+        ));
+    }
+    let grid: StarGrid =
+        fly_stars(center, "n", Flight::Forward)?;
+
+    let goou: Option<&str> = find_star(&grid, 4);
+    let anken: Option<&str> =
+        goou.map(get_opposite_direction);
+    let ha: &str = get_opposite_direction(
+        BRANCH_DIRECTIONS[branch % 12],
+    );
+    let honmei_satsu: Option<&str> =
+        find_star(&grid, honmei);
+    let honmei_teki_satsu: Option<&str> =
+        honmei_satsu.map(get_opposite_direction);
+
+    let directions = DIRECTIONS
+        .iter()
+        .map(|&dir| {
+            // Every direction has a star.
+            let star: usize = grid.get(dir).unwrap();
+            let relation: WuXingRelation =
+                get_wuxing_relation(
+                    element(honmei),
+                    element(star),
+                );
+
+            let mut reasons: Vec<LuckReason> = vec![];
+            if goou == Some(dir) {
+                reasons.push(LuckReason::GoouSatsu);
+            }
+            if anken == Some(dir) {
+                reasons.push(LuckReason::AnkenSatsu);
+            }
+            if ha == dir {
+                reasons.push(match period {
+                    LuckPeriod::Year => {
+                        LuckReason::SaiHa
+                    }
+                    LuckPeriod::Month => {
+                        LuckReason::GetsuHa
+                    }
+                    LuckPeriod::Day => {
+                        LuckReason::NichiHa
+                    }
+                });
+            }
+            if honmei_satsu == Some(dir) {
+                reasons.push(LuckReason::HonmeiSatsu);
+            }
+            if honmei_teki_satsu == Some(dir) {
+                reasons.push(
+                    LuckReason::HonmeiTekiSatsu,
+                );
+            }
+            if reasons.is_empty() {
+                reasons.push(
+                    if relation.is_compatible() {
+                        LuckReason::Compatible
+                    } else {
+                        LuckReason::Incompatible
+                    },
+                );
+            }
+
+            DirectionalLuck {
+                direction: dir.to_string(),
+                star,
+                relation,
+                auspicious: reasons
+                    == [LuckReason::Compatible],
+                reasons,
+            }
+        })
+        .collect();
+
+    Ok(DirectionalLuckChart {
+        period,
+        center,
+        branch: branch % 12,
+        directions,
+    })
+}
+
+/// Returns `DirectionalLuckChart` for the year, the
+/// month, and the day (in this order) for 本命星
+/// (Honmei-sei) of `honmei` at the given date and time
+/// (UT).
+///
+/// Example:
+/// ```rust
+/// use chrono::NaiveDate;
+/// use mikaboshi::kyusei::{get_directional_luck, DirectionalLuckChart, LuckPeriod};
+///
+/// let datetime = NaiveDate::from_ymd(2026, 10, 18).and_hms(3, 0, 0);
+/// let charts: Vec<DirectionalLuckChart> = get_directional_luck(0, datetime).unwrap();
+/// assert_eq!(charts[0].period, LuckPeriod::Year);
+/// assert_eq!(charts[1].period, LuckPeriod::Month);
+/// assert_eq!(charts[2].period, LuckPeriod::Day);
+/// ```
+pub fn get_directional_luck(
+    honmei: usize,
+    datetime: NaiveDateTime,
+) -> Result<Vec<DirectionalLuckChart>, JiuXingError> {
+    directional_luck_from_stars(
+        honmei,
+        datetime.date(),
+        year_from_lichun(
+            datetime,
+            get_instant_of_sun_longitude(
+                datetime.year(),
+                315.0,
+            ),
+        ),
+        monthly_star(datetime),
+        get_last_term_instant(datetime).0,
+        daily_star(datetime.date()),
+    )
+}
+
+/// The same as `get_directional_luck`, but calculates
+/// the position of the sun using the given provider.
+pub fn get_directional_luck_with(
+    provider: &dyn SunLongitudeProvider,
+    honmei: usize,
+    datetime: NaiveDateTime,
+) -> Result<Vec<DirectionalLuckChart>, JiuXingError> {
+    directional_luck_from_stars(
+        honmei,
+        datetime.date(),
+        year_from_lichun(
+            datetime,
+            get_instant_of_sun_longitude_with(
+                provider,
+                datetime.year(),
+                315.0,
+            ),
+        ),
+        monthly_star_with(provider, datetime),
+        get_last_term_instant_with(
+            provider, datetime,
+        )
+        .0,
+        daily_star_with(provider, datetime.date()),
+    )
+}
+
+fn directional_luck_from_stars(
+    honmei: usize,
+    date: NaiveDate,
+    year: i32,
+    month_star: usize,
+    last_term: f64,
+    day_star: usize,
+) -> Result<Vec<DirectionalLuckChart>, JiuXingError> {
+    // 0 for 子, 1 for 丑, ...
+    let year_branch: usize =
+        (year + 8).rem_euclid(12) as usize;
+    // The month of 立春 (Li-Chun) is 寅.
+    let month_branch: usize = ((last_term - 315.0)
+        .rem_euclid(360.0)
+        / 30.0)
+        .floor()
+        as usize
+        + 2;
+    let day_branch: usize =
+        get_day_ganzhi_from_date(date).branch.num
+            as usize
+            - 1;
+
+    Ok(vec![
+        directional_luck(
+            honmei,
+            annual_star(year),
+            year_branch,
+            LuckPeriod::Year,
+        )?,
+        directional_luck(
+            honmei,
+            month_star,
+            month_branch,
+            LuckPeriod::Month,
+        )?,
+        directional_luck(
+            honmei,
+            day_star,
+            day_branch,
+            LuckPeriod::Day,
+        )?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_keisha() {
+        // 七赤金星 (7 Red) and 三碧木星 (3 Jade)
+        // --> 離宮 (Ri-kyu)
+        assert_eq!(
+            keisha(6, 2, Gender::Male),
+            Some(8)
+        );
+        // 同会 (Dokai) for 七赤金星 (7 Red)
+        // --> 震宮 (Shin-kyu)
+        assert_eq!(
+            keisha(6, 6, Gender::Male),
+            Some(2)
+        );
+        // 同会 (Dokai) for 五黄土星 (5 Yellow)
+        assert_eq!(
+            keisha(4, 4, Gender::Male),
+            Some(5)
+        );
+        assert_eq!(
+            keisha(4, 4, Gender::Female),
+            Some(6)
+        );
+        assert_eq!(keisha(9, 4, Gender::Male), None);
+    }
+
+    #[test]
+    fn test_get_kyusei() {
+        let kyusei = get_kyusei(
+            NaiveDate::from_ymd(1984, 7, 15)
+                .and_hms(0, 0, 0),
+            Gender::Female,
+        );
+        assert_eq!(kyusei.honmei.index, 6);
+        assert_eq!(kyusei.getsumei.index, 2);
+        assert_eq!(kyusei.keisha.index, 8);
+        assert_eq!(kyusei.keisha_direction, "s");
+    }
+
+    #[test]
+    fn test_get_kyusei_before_lichun() {
+        // Belongs to 1985 (六白金星 (6 White)), and
+        // the month of 丑 (三碧木星 (3 Jade)).
+        let kyusei = get_kyusei(
+            NaiveDate::from_ymd(1986, 1, 20)
+                .and_hms(0, 0, 0),
+            Gender::Male,
+        );
+        assert_eq!(kyusei.honmei.index, 5);
+        assert_eq!(kyusei.getsumei.index, 2);
+    }
+
+    #[test]
+    fn test_directional_luck() {
+        // 七赤金星 (7 Red) when 三碧木星 (3 Jade) is in
+        // the center for the month of 寅.
+        let chart = directional_luck(
+            6,
+            2,
+            2,
+            LuckPeriod::Month,
+        )
+        .unwrap();
+        assert_eq!(chart.directions.len(), 8);
+        let reasons = |dir: &str| {
+            chart.get(dir).unwrap().reasons.clone()
+        };
+        assert_eq!(
+            reasons("w"),
+            vec![LuckReason::GoouSatsu]
+        );
+        assert_eq!(
+            reasons("e"),
+            vec![LuckReason::AnkenSatsu]
+        );
+        assert_eq!(
+            reasons("sw"),
+            vec![LuckReason::GetsuHa]
+        );
+        assert_eq!(
+            reasons("s"),
+            vec![LuckReason::HonmeiSatsu]
+        );
+        assert_eq!(
+            reasons("n"),
+            vec![LuckReason::HonmeiTekiSatsu]
+        );
+        // 四緑木星 (4 Green) is overcome by 七赤金星
+        // (7 Red).
+        let nw = chart.get("nw").unwrap();
+        assert_eq!(
+            nw.relation,
+            WuXingRelation::Overcomes
+        );
+        assert!(!nw.auspicious);
+        // 六白金星 (6 White) and 二黒土星 (2 Black)
+        assert!(chart.get("ne").unwrap().auspicious);
+        assert!(chart.get("se").unwrap().auspicious);
+
+        assert!(matches!(
+            directional_luck(
+                9,
+                2,
+                2,
+                LuckPeriod::Day
+            ),
+            Err(JiuXingError::InvalidStar(9))
+        ));
+    }
+
+    #[test]
+    fn test_get_directional_luck() {
+        let datetime =
+            NaiveDate::from_ymd(2026, 10, 18)
+                .and_hms(3, 0, 0);
+        let charts =
+            get_directional_luck(0, datetime)
+                .unwrap();
+        assert_eq!(charts.len(), 3);
+
+        // 丙午 with 一白水星 (1 White)
+        assert_eq!(charts[0].center, 0);
+        assert_eq!(charts[0].branch, 6);
+        // The month of 戌 with 九紫火星 (9 Purple)
+        assert_eq!(charts[1].center, 8);
+        assert_eq!(charts[1].branch, 10);
+        assert_eq!(
+            charts[2].center,
+            daily_star(datetime.date())
+        );
+        assert_eq!(
+            charts[2].branch + 1,
+            get_day_ganzhi_from_date(datetime.date())
+                .branch
+                .num as usize
+        );
+    }
+}
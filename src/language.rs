@@ -15,6 +15,24 @@ pub struct Language {
     pub zh_tw: LanguageDetails,
 }
 
+/// Languages available in `Language`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum LanguageKind {
+    En,
+    Ja,
+    Vi,
+    ZhCn,
+    ZhTw,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LanguageData {
     pub en: String,
@@ -36,6 +54,36 @@ impl LanguageDetails {
     }
 }
 
+impl Language {
+    /// Returns the name in the given language (the
+    /// alphabet except for English). Falls back to
+    /// English when the language has none.
+    ///
+    /// Example:
+    /// ```rust
+    /// use mikaboshi::jiuxing::JIU_XING;
+    /// use mikaboshi::language::LanguageKind;
+    ///
+    /// let name = &JIU_XING[0].name;
+    /// assert_eq!(name.text(LanguageKind::ZhTw), "一白水星");
+    /// assert_eq!(name.text(LanguageKind::Vi), "1 White");
+    /// ```
+    pub fn text(&self, kind: LanguageKind) -> &str {
+        let details: &LanguageDetails = match kind {
+            LanguageKind::En => return &self.en,
+            LanguageKind::Ja => &self.ja,
+            LanguageKind::Vi => &self.vi,
+            LanguageKind::ZhCn => &self.zh_cn,
+            LanguageKind::ZhTw => &self.zh_tw,
+        };
+        if details.alphabet.is_empty() {
+            &self.en
+        } else {
+            &details.alphabet
+        }
+    }
+}
+
 pub trait LanguageTrait {
     fn name(&self) -> Box<Language>;
 
@@ -3,15 +3,30 @@ extern crate chrono;
 #[macro_use]
 extern crate lazy_static;
 
+pub mod afflictions;
 pub mod bagua;
+pub mod bazhai;
+pub mod castle_gate;
 pub mod compass;
+pub mod ephemeris;
+pub mod floor_plan;
+pub mod formations;
 pub mod ganzhi;
 pub mod jiuxing;
+pub mod kyusei;
 pub mod language;
+pub mod ling_zheng;
+pub mod overlay;
 pub mod planet;
+#[cfg(feature = "render")]
+pub mod render;
+pub mod sanyuan;
 pub mod shengsi;
 pub mod solar_terms;
+pub mod star_pairs;
+pub mod text;
 pub mod utils;
 pub mod wuxing;
+pub mod zassetsu;
 
 pub mod test_mods;
@@ -0,0 +1,331 @@
+//! 零神 (Ling-Shen) and 正神 (Zheng-Shen) are the
+//! directions which tell where water and mountains
+//! should be for each period of 三元九運 (Sang-Yuan
+//! Jiu-Yun). 正神 (Zheng-Shen) is the original palace
+//! of 運盤星 (Un-Pan Xing) for the period, and it
+//! wants mountains. 零神 (Ling-Shen) is the opposite,
+//! and it wants water. When water is found in 正神
+//! (Zheng-Shen), or mountains in 零神 (Ling-Shen), it
+//! is called 零正顛倒 (Ling-Zheng Dian-Dao), and is
+//! considered unfavorable.
+//!
+//! 五運 (Period 5) has no palace of its own, and
+//! borrows "sw" (坤) for the first 10 years, and "ne"
+//! (艮) for the last 10 years.
+
+use chrono::naive::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::compass::{
+    get_opposite_direction,
+    get_twentyfour_direction_from_degrees, Direction,
+};
+use crate::jiuxing::{
+    unpan_xing_index, JiuXingError, JIU_XING,
+    SAN_YUAN_JIU_YUN_START_YEAR,
+};
+use crate::solar_terms::year_from_lichun;
+
+/// A struct representing 零神 (Ling-Shen) and 正神
+/// (Zheng-Shen) for the period. `period` is 運盤星
+/// (Un-Pan Xing) index.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct LingZhengShen {
+    pub period: usize,
+    pub zheng_shen: String,
+    pub ling_shen: String,
+}
+
+/// Kinds of landforms.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum LandformKind {
+    Water,
+    Mountain,
+}
+
+/// A struct representing a landform found around
+/// the site. `bearing` is the direction in degrees
+/// seen from the site.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Landform {
+    pub kind: LandformKind,
+    pub bearing: f32,
+}
+
+/// Whether the landform is placed well.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum Placement {
+    Favorable,
+    Unfavorable,
+    Neutral,
+}
+
+/// A struct representing the evaluation for a
+/// landform. `direction` is 二十四山向 (Er-Shi-Si
+/// Shan-Xiang) where the landform is found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LandformEvaluation {
+    pub landform: Landform,
+    pub direction: Direction,
+    pub placement: Placement,
+}
+
+lazy_static! {
+    /// A static vector with 9 items, each represents
+    /// 零神 (Ling-Shen) and 正神 (Zheng-Shen) for the
+    /// period (where the index being 運盤星 (Un-Pan
+    /// Xing) index). For 五運 (Period 5), it is that
+    /// of the first 10 years.
+    pub static ref LING_ZHENG_SHEN: Vec<LingZhengShen> = (0..9)
+        .map(|period: usize| {
+            let zheng_shen: &str = match period {
+                4 => "sw",
+                _ => JIU_XING[period].direction.as_str(),
+            };
+            LingZhengShen {
+                period,
+                zheng_shen: zheng_shen.to_string(),
+                ling_shen: get_opposite_direction(zheng_shen)
+                    .to_string(),
+            }
+        })
+        .collect();
+}
+
+/// A getter for `LING_ZHENG_SHEN`.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::ling_zheng::get_ling_zheng_shen;
+///
+/// // 八運 (Period 8)
+/// let shen = get_ling_zheng_shen(7).unwrap();
+/// assert_eq!(shen.zheng_shen, "ne");
+/// assert_eq!(shen.ling_shen, "sw");
+/// ```
+pub fn get_ling_zheng_shen(
+    period: usize,
+) -> Option<&'static LingZhengShen> {
+    LING_ZHENG_SHEN.get(period)
+}
+
+/// Returns 零神 (Ling-Shen) and 正神 (Zheng-Shen) for
+/// the given date (for which the period is found
+/// using `unpan_xing_index`). For 五運 (Period 5), the
+/// last 10 years borrow "ne" (艮).
+pub fn ling_zheng_shen(
+    current: NaiveDate,
+    lichun: NaiveDate,
+) -> LingZhengShen {
+    let period: usize =
+        unpan_xing_index(current, lichun);
+    let shen: &LingZhengShen =
+        &LING_ZHENG_SHEN[period];
+    // Both are dates, and are compared at midnight.
+    let year: i32 = year_from_lichun(
+        current.and_hms(0, 0, 0),
+        lichun.and_hms(0, 0, 0),
+    ) - SAN_YUAN_JIU_YUN_START_YEAR
+        as i32;
+
+    if period == 4 && year.rem_euclid(20) >= 10 {
+        LingZhengShen {
+            period,
+            zheng_shen: shen.ling_shen.clone(),
+            ling_shen: shen.zheng_shen.clone(),
+        }
+    } else {
+        shen.clone()
+    }
+}
+
+/// Evaluates the given landforms against 零神
+/// (Ling-Shen) and 正神 (Zheng-Shen). Water is
+/// favorable in 零神 (Ling-Shen), and mountains in
+/// 正神 (Zheng-Shen). The opposite is unfavorable,
+/// and the rest are neutral. Returns an error when
+/// any of the bearings is not a finite number.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::ling_zheng::{
+///     evaluate_landforms, get_ling_zheng_shen, Landform, LandformKind, Placement,
+/// };
+///
+/// // 八運 (Period 8)
+/// let shen = get_ling_zheng_shen(7).unwrap();
+/// let landforms = [
+///     Landform { kind: LandformKind::Water, bearing: 225.0 },
+///     Landform { kind: LandformKind::Mountain, bearing: 225.0 },
+/// ];
+/// let res = evaluate_landforms(shen, &landforms).unwrap();
+/// assert_eq!(res[0].placement, Placement::Favorable);
+/// assert_eq!(res[1].placement, Placement::Unfavorable);
+/// ```
+pub fn evaluate_landforms(
+    shen: &LingZhengShen,
+    landforms: &[Landform],
+) -> Result<Vec<LandformEvaluation>, JiuXingError> {
+    landforms
+        .iter()
+        .map(|landform| {
+            if !landform.bearing.is_finite() {
+                return Err(
+                    JiuXingError::InvalidDegrees(
+                        landform.bearing,
+                    ),
+                );
+            }
+            let direction: Direction =
+                get_twentyfour_direction_from_degrees(
+                    landform
+                        .bearing
+                        .rem_euclid(360.0),
+                );
+            let dir: &str =
+                direction.direction.as_str();
+            let placement: Placement =
+                match landform.kind {
+                    LandformKind::Water
+                        if dir == shen.ling_shen =>
+                    {
+                        Placement::Favorable
+                    }
+                    LandformKind::Water
+                        if dir == shen.zheng_shen =>
+                    {
+                        Placement::Unfavorable
+                    }
+                    LandformKind::Mountain
+                        if dir == shen.zheng_shen =>
+                    {
+                        Placement::Favorable
+                    }
+                    LandformKind::Mountain
+                        if dir == shen.ling_shen =>
+                    {
+                        Placement::Unfavorable
+                    }
+                    _ => Placement::Neutral,
+                };
+            Ok(LandformEvaluation {
+                landform: landform.clone(),
+                direction,
+                placement,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_ling_zheng_shen() {
+        assert_eq!(LING_ZHENG_SHEN.len(), 9);
+        // 一運 (Period 1)
+        assert_eq!(
+            LING_ZHENG_SHEN[0].zheng_shen,
+            "n"
+        );
+        assert_eq!(LING_ZHENG_SHEN[0].ling_shen, "s");
+        // 九運 (Period 9)
+        assert_eq!(
+            LING_ZHENG_SHEN[8].zheng_shen,
+            "s"
+        );
+        assert_eq!(LING_ZHENG_SHEN[8].ling_shen, "n");
+    }
+
+    #[test]
+    fn test_ling_zheng_shen_for_period_5() {
+        let lichun = NaiveDate::from_ymd(1944, 2, 5);
+        let shen = ling_zheng_shen(
+            NaiveDate::from_ymd(1944, 3, 1),
+            lichun,
+        );
+        assert_eq!(shen.period, 4);
+        assert_eq!(shen.zheng_shen, "sw");
+
+        let lichun = NaiveDate::from_ymd(1954, 2, 4);
+        let shen = ling_zheng_shen(
+            NaiveDate::from_ymd(1954, 3, 1),
+            lichun,
+        );
+        assert_eq!(shen.period, 4);
+        assert_eq!(shen.zheng_shen, "ne");
+        assert_eq!(shen.ling_shen, "sw");
+    }
+
+    #[test]
+    fn test_evaluate_landforms() {
+        // 九運 (Period 9)
+        let shen = get_ling_zheng_shen(8).unwrap();
+        let landforms = [
+            Landform {
+                kind: LandformKind::Water,
+                bearing: 355.0,
+            },
+            Landform {
+                kind: LandformKind::Water,
+                bearing: -175.0,
+            },
+            Landform {
+                kind: LandformKind::Mountain,
+                bearing: 90.0,
+            },
+        ];
+        let res =
+            evaluate_landforms(shen, &landforms)
+                .unwrap();
+        assert_eq!(
+            res[0].placement,
+            Placement::Favorable
+        );
+        assert_eq!(
+            res[0].direction,
+            Direction::new("n", 2)
+        );
+        assert_eq!(
+            res[1].placement,
+            Placement::Unfavorable
+        );
+        assert_eq!(
+            res[2].placement,
+            Placement::Neutral
+        );
+
+        let nan = [Landform {
+            kind: LandformKind::Water,
+            bearing: f32::NAN,
+        }];
+        assert!(matches!(
+            evaluate_landforms(shen, &nan),
+            Err(JiuXingError::InvalidDegrees(_))
+        ));
+    }
+}
@@ -0,0 +1,304 @@
+//! In 玄空飞星風水 (Xuan-Kong Fei-Xing Feng-Shui),
+//! 九星 (Jiu-Xing) for the year and the month visit
+//! each palace of the natal chart, and this is called
+//! 加臨 (Jia-Lin). Readings for a given time are made
+//! from the stack of stars in each palace:
+//!
+//! - 運盤星 (Un-Pan Xing)
+//! - 山星 (Shan-Xing)
+//! - 向星 (Xiang-Xing)
+//! - 年紫白 (Nian Zi-Bai)
+//! - 月紫白 (Yue Zi-Bai)
+//!
+//! Timeliness of the stars (生死衰旺 (Sheng-Si
+//! Shuai-Wang)) is that of the current period, not
+//! the period the building was built in.
+
+use chrono::naive::NaiveDateTime;
+use chrono::Datelike;
+use serde::Serialize;
+
+use crate::ephemeris::SunLongitudeProvider;
+use crate::jiuxing::{
+    annual_star, fly_stars, monthly_star,
+    monthly_star_with, Flight, JiuXingError,
+    NatalChart, StarGrid, LO_SHU_FLIGHT_PATH,
+};
+use crate::sanyuan::Period;
+use crate::shengsi::{get_shengsi, ShengSi};
+use crate::solar_terms::{
+    get_instant_of_sun_longitude,
+    get_instant_of_sun_longitude_with,
+    year_from_lichun,
+};
+use crate::star_pairs::{
+    adjust_fortune, get_star_pair, Fortune, StarPair,
+};
+
+/// A struct representing the stack of stars in a
+/// palace. `direction` is that of the palace ("" being
+/// the center), and the stars are 九星 (Jiu-Xing)
+/// indexes. `pair` is for `mountain` and `facing`, and
+/// `fortune` is that of `pair` adjusted by the
+/// timeliness of the two stars for the current period.
+#[derive(Debug, Clone, Serialize)]
+pub struct PalaceStack {
+    pub direction: &'static str,
+    pub period: usize,
+    pub mountain: usize,
+    pub facing: usize,
+    pub annual: usize,
+    pub monthly: usize,
+    pub mountain_shengsi:
+        Option<&'static ShengSi<'static>>,
+    pub facing_shengsi:
+        Option<&'static ShengSi<'static>>,
+    pub annual_shengsi:
+        Option<&'static ShengSi<'static>>,
+    pub monthly_shengsi:
+        Option<&'static ShengSi<'static>>,
+    pub pair: &'static StarPair,
+    pub fortune: Fortune,
+}
+
+/// A struct representing the natal chart overlaid with
+/// 九星 (Jiu-Xing) for the year and the month.
+/// `current_period` is the period for the given time,
+/// and `annual_star` and `monthly_star` are those in
+/// the center. `palaces` are in the order of
+/// `LO_SHU_FLIGHT_PATH`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChartOverlay {
+    pub current_period: usize,
+    pub annual_star: usize,
+    pub monthly_star: usize,
+    pub palaces: Vec<PalaceStack>,
+}
+
+impl ChartOverlay {
+    /// Returns `PalaceStack` for the given direction
+    /// ("" being the center).
+    pub fn get(
+        &self,
+        dir: &str,
+    ) -> Option<&PalaceStack> {
+        self.palaces
+            .iter()
+            .find(|palace| palace.direction == dir)
+    }
+}
+
+/// Returns `ChartOverlay` for the given `NatalChart`
+/// when `current_period` (運盤星 (Un-Pan Xing) index
+/// for the current period) rules, and `annual` and
+/// `monthly` (九星 (Jiu-Xing) indexes) are in the
+/// center.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::jiuxing::get_natal_chart;
+/// use mikaboshi::overlay::chart_overlay;
+///
+/// // 八運 (Period 8), 子山午向 in 九運 (Period 9)
+/// // with 三碧木星 (3 Jade) for the year and
+/// // 五黄土星 (5 Yellow) for the month.
+/// let chart = get_natal_chart(7, "s", 2, "n").unwrap();
+/// let overlay = chart_overlay(&chart, 8, 2, 4).unwrap();
+/// let s = overlay.get("s").unwrap();
+/// assert_eq!((s.period, s.mountain, s.facing), (2, 7, 7));
+/// assert_eq!((s.annual, s.monthly), (6, 8));
+/// ```
+pub fn chart_overlay(
+    chart: &NatalChart,
+    current_period: usize,
+    annual: usize,
+    monthly: usize,
+) -> Result<ChartOverlay, JiuXingError> {
+    if current_period > 8 {
+        return Err(JiuXingError::InvalidStar(
+            current_period,
+        ));
+    }
+    let annual_grid: StarGrid =
+        fly_stars(annual, "n", Flight::Forward)?;
+    let monthly_grid: StarGrid =
+        fly_stars(monthly, "n", Flight::Forward)?;
+
+    let palaces = LO_SHU_FLIGHT_PATH
+        .iter()
+        .filter_map(|&direction| {
+            let (period, mountain, facing) =
+                chart.palace(direction)?;
+            let annual =
+                annual_grid.get(direction)?;
+            let monthly =
+                monthly_grid.get(direction)?;
+            let shengsi = |index| {
+                get_shengsi(current_period, index)
+            };
+            let pair =
+                get_star_pair(mountain, facing);
+            Some(PalaceStack {
+                direction,
+                period,
+                mountain,
+                facing,
+                annual,
+                monthly,
+                mountain_shengsi: shengsi(mountain),
+                facing_shengsi: shengsi(facing),
+                annual_shengsi: shengsi(annual),
+                monthly_shengsi: shengsi(monthly),
+                pair,
+                fortune: adjust_fortune(
+                    pair.fortune,
+                    shengsi(mountain),
+                    shengsi(facing),
+                ),
+            })
+        })
+        .collect();
+
+    Ok(ChartOverlay {
+        current_period,
+        annual_star: annual,
+        monthly_star: monthly,
+        palaces,
+    })
+}
+
+/// Returns `ChartOverlay` for the given `NatalChart`
+/// at the given date and time (UT).
+///
+/// Example:
+/// ```rust
+/// use chrono::NaiveDate;
+/// use mikaboshi::jiuxing::get_natal_chart;
+/// use mikaboshi::overlay::{get_chart_overlay, ChartOverlay};
+///
+/// let chart = get_natal_chart(7, "s", 2, "n").unwrap();
+/// let datetime = NaiveDate::from_ymd(2024, 2, 10).and_hms(0, 0, 0);
+/// let overlay: ChartOverlay = get_chart_overlay(&chart, datetime);
+/// assert_eq!(overlay.current_period, 8);
+/// assert_eq!(overlay.annual_star, 2);
+/// assert_eq!(overlay.monthly_star, 4);
+/// ```
+pub fn get_chart_overlay(
+    chart: &NatalChart,
+    datetime: NaiveDateTime,
+) -> ChartOverlay {
+    overlay_from_lichun(
+        chart,
+        datetime,
+        get_instant_of_sun_longitude(
+            datetime.year(),
+            315.0,
+        ),
+        monthly_star(datetime),
+    )
+}
+
+/// The same as `get_chart_overlay`, but calculates the
+/// position of the sun using the given provider.
+pub fn get_chart_overlay_with(
+    provider: &dyn SunLongitudeProvider,
+    chart: &NatalChart,
+    datetime: NaiveDateTime,
+) -> ChartOverlay {
+    overlay_from_lichun(
+        chart,
+        datetime,
+        get_instant_of_sun_longitude_with(
+            provider,
+            datetime.year(),
+            315.0,
+        ),
+        monthly_star_with(provider, datetime),
+    )
+}
+
+fn overlay_from_lichun(
+    chart: &NatalChart,
+    datetime: NaiveDateTime,
+    lichun: NaiveDateTime,
+    monthly: usize,
+) -> ChartOverlay {
+    let year: i32 =
+        year_from_lichun(datetime, lichun);
+    // The period and the stars are all valid
+    // 九星 (Jiu-Xing) indexes.
+    chart_overlay(
+        chart,
+        Period::from_year(year).index(),
+        annual_star(year),
+        monthly,
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jiuxing::get_natal_chart;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_chart_overlay() {
+        // 八運 (Period 8), 子山午向
+        let chart =
+            get_natal_chart(7, "s", 2, "n").unwrap();
+        let overlay =
+            chart_overlay(&chart, 8, 2, 4).unwrap();
+        assert_eq!(overlay.palaces.len(), 9);
+
+        let center = overlay.get("").unwrap();
+        assert_eq!(
+            (
+                center.period,
+                center.annual,
+                center.monthly
+            ),
+            (7, 2, 4)
+        );
+
+        // 八白土星 (8 White) is 衰 (Shuai), and
+        // 九紫火星 (9 Purple) is 旺 (Wang) in 九運
+        // (Period 9).
+        let s = overlay.get("s").unwrap();
+        assert_eq!((s.mountain, s.facing), (7, 7));
+        assert_eq!(
+            s.facing_shengsi.unwrap().key,
+            "shuai"
+        );
+        let sw = overlay.get("sw").unwrap();
+        assert_eq!(sw.annual, 8);
+        assert_eq!(
+            sw.annual_shengsi.unwrap().key,
+            "wang"
+        );
+
+        assert!(matches!(
+            chart_overlay(&chart, 9, 2, 4),
+            Err(JiuXingError::InvalidStar(9))
+        ));
+        assert!(matches!(
+            chart_overlay(&chart, 8, 2, 9),
+            Err(JiuXingError::InvalidStar(9))
+        ));
+    }
+
+    #[test]
+    fn test_get_chart_overlay() {
+        let chart =
+            get_natal_chart(7, "s", 2, "n").unwrap();
+        // Before 立春 (Li-Chun), it is still 八運
+        // (Period 8) and 2023 (四緑木星 (4 Green)).
+        let overlay = get_chart_overlay(
+            &chart,
+            NaiveDate::from_ymd(2024, 1, 20)
+                .and_hms(0, 0, 0),
+        );
+        assert_eq!(overlay.current_period, 7);
+        assert_eq!(overlay.annual_star, 3);
+    }
+}
@@ -0,0 +1,419 @@
+//! A module for rendering charts as SVG. Only
+//! available with `render` feature. For plain text,
+//! see `text`.
+//!
+//! (1) `render_chart_svg` --> 3x3 flying-star chart.
+//! Each box has 山星 (Shan-Xing) on the top left,
+//! 向星 (Xiang-Xing) on the top right, 運盤星 (Un-Pan
+//! Xing) on the bottom, and the direction on the top.
+//! (2) `render_star_grid_svg` --> 3x3 chart for any
+//! `StarGrid` (for instance, 年紫白 (Nian Zi-Bai)).
+//! (3) `render_luopan_svg` --> 羅盤 (Luopan) with
+//! 八卦 (Ba-Gua) for the inner ring, 8 directions for
+//! the middle ring, and 二十四山向 (Er-Shi-Si
+//! Shan-Xiang) for the outer ring.
+//!
+//! SVG is returned as `String`.
+
+use std::f64::consts::PI;
+
+use crate::bagua::BAGUA_LO_SHU_COMPASS_ORDER;
+use crate::compass::{
+    get_twentyfour_data_from_index, TwentyFourType,
+    DIRECTIONS, DIRECTION_POSITIONS_IN_CHART,
+};
+use crate::jiuxing::{NatalChart, StarGrid};
+use crate::language::{Language, LanguageKind};
+use crate::text::direction_label;
+
+/// Options for rendering. `size` is the width (and the
+/// height) of SVG, and `language` is used for labels.
+/// `rotation` is the compass bearing (in degrees) which
+/// comes to the top of 羅盤 (Luopan) (it has no effect
+/// on 3x3 charts).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderOptions {
+    pub size: f64,
+    pub language: LanguageKind,
+    pub rotation: f64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            size: 360.0,
+            language: LanguageKind::ZhTw,
+            rotation: 0.0,
+        }
+    }
+}
+
+/// Escapes characters for XML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn svg_open(size: f64) -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{s}" height="{s}" viewBox="0 0 {s} {s}">"#,
+        s = size
+    )
+}
+
+fn text(
+    x: f64,
+    y: f64,
+    font_size: f64,
+    class: &str,
+    label: &str,
+) -> String {
+    format!(
+        r#"<text class="{}" x="{:.2}" y="{:.2}" font-size="{:.2}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+        class,
+        x,
+        y,
+        font_size,
+        escape(label)
+    )
+}
+
+/// Renders 3x3 boxes where `cell` returns the labels
+/// (top left, top right, bottom) for the direction.
+fn render_boxes<F>(
+    layout: &str,
+    options: &RenderOptions,
+    cell: F,
+) -> Option<String>
+where
+    F: Fn(&str) -> Option<[String; 3]>,
+{
+    let positions: &[&str; 9] =
+        DIRECTION_POSITIONS_IN_CHART.get(layout)?;
+    let unit: f64 = options.size / 3.0;
+    let mut svg: String = svg_open(options.size);
+
+    for (i, dir) in positions.iter().enumerate() {
+        let x: f64 = unit * (i % 3) as f64;
+        let y: f64 = unit * (i / 3) as f64;
+        let [top_left, top_right, bottom] =
+            cell(dir)?;
+        svg.push_str(&format!(
+            r#"<g class="palace" data-direction="{}">"#,
+            dir
+        ));
+        svg.push_str(&format!(
+            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="none" stroke="black"/>"#,
+            x, y, unit, unit
+        ));
+        svg.push_str(&text(
+            x + unit * 0.5,
+            y + unit * 0.15,
+            unit * 0.12,
+            "direction",
+            &direction_label(dir, options.language),
+        ));
+        svg.push_str(&text(
+            x + unit * 0.3,
+            y + unit * 0.45,
+            unit * 0.2,
+            "mountain",
+            &top_left,
+        ));
+        svg.push_str(&text(
+            x + unit * 0.7,
+            y + unit * 0.45,
+            unit * 0.2,
+            "facing",
+            &top_right,
+        ));
+        svg.push_str(&text(
+            x + unit * 0.5,
+            y + unit * 0.78,
+            unit * 0.24,
+            "center",
+            &bottom,
+        ));
+        svg.push_str("</g>");
+    }
+    svg.push_str("</svg>");
+    Some(svg)
+}
+
+/// Returns SVG for the given `NatalChart`. The boxes
+/// are laid out for `chart.period.direction` (see
+/// `DIRECTION_POSITIONS_IN_CHART`), and stars are shown
+/// as numbers (from 1 to 9).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::jiuxing::get_natal_chart;
+/// use mikaboshi::render::{render_chart_svg, RenderOptions};
+///
+/// let chart = get_natal_chart(7, "s", 2, "n").unwrap();
+/// let svg: String = render_chart_svg(&chart, &RenderOptions::default());
+/// assert!(svg.starts_with("<svg"));
+/// assert!(svg.contains(">東南<"));
+/// ```
+pub fn render_chart_svg(
+    chart: &NatalChart,
+    options: &RenderOptions,
+) -> String {
+    // `NatalChart` always has a valid layout.
+    render_boxes(
+        &chart.period.direction,
+        options,
+        |dir| {
+            let (period, mountain, facing) =
+                chart.palace(dir)?;
+            Some([
+                (mountain + 1).to_string(),
+                (facing + 1).to_string(),
+                (period + 1).to_string(),
+            ])
+        },
+    )
+    .unwrap()
+}
+
+/// Returns SVG for the given `StarGrid`. Only the
+/// bottom of each box is used.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::jiuxing::{fly_stars, Flight};
+/// use mikaboshi::language::LanguageKind;
+/// use mikaboshi::render::{render_star_grid_svg, RenderOptions};
+///
+/// // 年紫白 (Nian Zi-Bai) for 2024
+/// let grid = fly_stars(2, "n", Flight::Forward).unwrap();
+/// let options = RenderOptions {
+///     language: LanguageKind::En,
+///     ..RenderOptions::default()
+/// };
+/// let svg: String = render_star_grid_svg(&grid, &options);
+/// assert!(svg.contains(">northwest<"));
+/// ```
+pub fn render_star_grid_svg(
+    grid: &StarGrid,
+    options: &RenderOptions,
+) -> String {
+    // `StarGrid` always has a valid layout.
+    render_boxes(&grid.direction, options, |dir| {
+        Some([
+            String::new(),
+            String::new(),
+            (grid.get(dir)? + 1).to_string(),
+        ])
+    })
+    .unwrap()
+}
+
+/// Point on the screen for the given compass bearing
+/// and the radius.
+fn polar(
+    options: &RenderOptions,
+    bearing: f64,
+    radius: f64,
+) -> (f64, f64) {
+    let center: f64 = options.size / 2.0;
+    let rad: f64 =
+        (bearing - options.rotation) * PI / 180.0;
+    (
+        center + radius * rad.sin(),
+        center - radius * rad.cos(),
+    )
+}
+
+/// Renders a ring with labels where each section is
+/// centered at `i * 360 / labels.len()` degrees.
+fn ring(
+    options: &RenderOptions,
+    class: &str,
+    inner: f64,
+    outer: f64,
+    labels: &[String],
+) -> String {
+    let span: f64 = 360.0 / labels.len() as f64;
+    let mut svg: String =
+        format!(r#"<g class="{}">"#, class);
+
+    for (i, label) in labels.iter().enumerate() {
+        let mid: f64 = span * i as f64;
+        let (start, end) =
+            (mid - span / 2.0, mid + span / 2.0);
+        let (x0, y0) = polar(options, start, outer);
+        let (x1, y1) = polar(options, end, outer);
+        let (x2, y2) = polar(options, end, inner);
+        let (x3, y3) = polar(options, start, inner);
+        svg.push_str(&format!(
+            r#"<path d="M {:.2} {:.2} A {r1:.2} {r1:.2} 0 0 1 {:.2} {:.2} L {:.2} {:.2} A {r2:.2} {r2:.2} 0 0 0 {:.2} {:.2} Z" fill="none" stroke="black"/>"#,
+            x0, y0, x1, y1, x2, y2, x3, y3,
+            r1 = outer,
+            r2 = inner
+        ));
+
+        // Labels are rotated so that they face the
+        // center.
+        let (x, y) = polar(
+            options,
+            mid,
+            (inner + outer) / 2.0,
+        );
+        svg.push_str(&format!(
+            r#"<g transform="rotate({:.2} {:.2} {:.2})">"#,
+            mid - options.rotation,
+            x,
+            y
+        ));
+        svg.push_str(&text(
+            x,
+            y,
+            (outer - inner) * 0.4,
+            "label",
+            label,
+        ));
+        svg.push_str("</g>");
+    }
+    svg.push_str("</g>");
+    svg
+}
+
+/// Returns SVG for 羅盤 (Luopan).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::language::LanguageKind;
+/// use mikaboshi::render::{render_luopan_svg, RenderOptions};
+///
+/// let options = RenderOptions {
+///     size: 480.0,
+///     language: LanguageKind::Ja,
+///     rotation: 45.0,
+/// };
+/// let svg: String = render_luopan_svg(&options);
+/// assert!(svg.contains(">北東<"));
+/// ```
+pub fn render_luopan_svg(
+    options: &RenderOptions,
+) -> String {
+    let radius: f64 = options.size / 2.0 - 1.0;
+    let name = |language: &Language| {
+        language.text(options.language).to_string()
+    };
+
+    let bagua: Vec<String> =
+        BAGUA_LO_SHU_COMPASS_ORDER
+            .iter()
+            .map(|gua| name(&gua.name))
+            .collect();
+    let directions: Vec<String> = DIRECTIONS
+        .iter()
+        .map(|dir| {
+            direction_label(dir, options.language)
+        })
+        .collect();
+    let mountains: Vec<String> = (0..24)
+        .map(
+            |i| match get_twentyfour_data_from_index(
+                i,
+            ) {
+                TwentyFourType::Gua(gua) => {
+                    name(&gua.name)
+                }
+                TwentyFourType::Stem(stem) => {
+                    name(&stem.name)
+                }
+                TwentyFourType::Branch(branch) => {
+                    name(&branch.name)
+                }
+            },
+        )
+        .collect();
+
+    let mut svg: String = svg_open(options.size);
+    svg.push_str(&ring(
+        options,
+        "bagua",
+        radius * 0.25,
+        radius * 0.5,
+        &bagua,
+    ));
+    svg.push_str(&ring(
+        options,
+        "directions",
+        radius * 0.5,
+        radius * 0.75,
+        &directions,
+    ));
+    svg.push_str(&ring(
+        options,
+        "mountains",
+        radius * 0.75,
+        radius,
+        &mountains,
+    ));
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jiuxing::get_natal_chart;
+
+    #[test]
+    fn test_render_chart_svg() {
+        // 八運 (Period 8), 子山午向
+        let chart =
+            get_natal_chart(7, "s", 2, "n").unwrap();
+        let svg = render_chart_svg(
+            &chart,
+            &RenderOptions::default(),
+        );
+        assert_eq!(svg.matches("<rect").count(), 9);
+        // 8-8 at the facing.
+        let start = svg
+            .find(r#"data-direction="s""#)
+            .unwrap();
+        let end = start
+            + svg[start..].find("</g>").unwrap();
+        let palace = &svg[start..end];
+        assert!(palace.contains(">南<"));
+        assert_eq!(palace.matches(">8<").count(), 2);
+        assert!(palace.contains(">3<"));
+        assert!(svg.ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_render_luopan_svg() {
+        let svg = render_luopan_svg(&RenderOptions {
+            size: 200.0,
+            language: LanguageKind::ZhTw,
+            rotation: 0.0,
+        });
+        assert_eq!(svg.matches("<path").count(), 40);
+        assert!(svg.contains(">子<"));
+        assert!(svg.contains(">坎<"));
+
+        // The north comes to the top only without
+        // rotation.
+        let rotated =
+            render_luopan_svg(&RenderOptions {
+                size: 200.0,
+                language: LanguageKind::ZhTw,
+                rotation: 90.0,
+            });
+        assert_ne!(svg, rotated);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("<a & b>"),
+            "&lt;a &amp; b&gt;"
+        );
+    }
+}
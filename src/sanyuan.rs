@@ -0,0 +1,308 @@
+//! 三元九運 (Sang-Yuan Jiu-Yun), or "9 Yearly Cycles",
+//! divides 180 years into 9 periods (運) of 20 years,
+//! each of which is ruled by one of 九星 (Jiu-Xing).
+//! The 9 periods are grouped into 3 元 (Yuan) of 60
+//! years:
+//!
+//! - 上元 (Shang-Yuan) --> 一運, 二運, 三運
+//! - 中元 (Zhong-Yuan) --> 四運, 五運, 六運
+//! - 下元 (Xia-Yuan) --> 七運, 八運, 九運
+//!
+//! The current cycle began in 1864, and 九運 (Period
+//! 9) runs from 2024 to 2044. Just like years for
+//! 風水 (Feng-Shui), periods begin from 立春
+//! (Li-Chun).
+
+use chrono::naive::NaiveDateTime;
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+use crate::ephemeris::SunLongitudeProvider;
+use crate::jiuxing::SAN_YUAN_JIU_YUN_START_YEAR;
+use crate::solar_terms::{
+    get_instant_of_sun_longitude,
+    get_instant_of_sun_longitude_with,
+    year_from_lichun,
+};
+
+/// 上元 (Shang-Yuan), 中元 (Zhong-Yuan), or 下元
+/// (Xia-Yuan).
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum Yuan {
+    Upper,  // 上元
+    Middle, // 中元
+    Lower,  // 下元
+}
+
+/// A struct representing a period of 三元九運
+/// (Sang-Yuan Jiu-Yun). `num` is the period number
+/// (from 1 to 9). The period begins from 立春
+/// (Li-Chun) of `start_year`, and ends at 立春
+/// (Li-Chun) of `end_year` (when the next period
+/// begins).
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct Period {
+    pub num: usize,
+    pub yuan: Yuan,
+    pub start_year: i32,
+    pub end_year: i32,
+}
+
+impl Period {
+    /// Returns `Period` for the given year (which
+    /// begins from 立春 (Li-Chun)). Works for years
+    /// before 1864 as well.
+    ///
+    /// Example:
+    /// ```rust
+    /// use mikaboshi::sanyuan::{Period, Yuan};
+    ///
+    /// let period = Period::from_year(2024);
+    /// assert_eq!(period.num, 9);
+    /// assert_eq!(period.yuan, Yuan::Lower);
+    /// assert_eq!(period.start_year, 2024);
+    /// assert_eq!(period.end_year, 2044);
+    /// ```
+    pub fn from_year(year: i32) -> Self {
+        let dt: i32 =
+            year - SAN_YUAN_JIU_YUN_START_YEAR as i32;
+        let index: i32 = dt.rem_euclid(180) / 20;
+        let start_year: i32 =
+            year - dt.rem_euclid(20);
+        Period {
+            num: index as usize + 1,
+            yuan: match index / 3 {
+                0 => Yuan::Upper,
+                1 => Yuan::Middle,
+                _ => Yuan::Lower,
+            },
+            start_year,
+            end_year: start_year + 20,
+        }
+    }
+
+    /// 九星 (Jiu-Xing) index for 運盤星 (Un-Pan Xing)
+    /// of the period.
+    pub fn index(&self) -> usize {
+        self.num - 1
+    }
+
+    /// Whether the given year (which begins from 立春
+    /// (Li-Chun)) is within the period.
+    pub fn contains_year(&self, year: i32) -> bool {
+        (self.start_year..self.end_year)
+            .contains(&year)
+    }
+
+    /// The instant (UT) of 立春 (Li-Chun) when the
+    /// period begins.
+    pub fn start(&self) -> NaiveDateTime {
+        get_instant_of_sun_longitude(
+            self.start_year,
+            315.0,
+        )
+    }
+
+    /// The instant (UT) of 立春 (Li-Chun) when the
+    /// period ends (and the next period begins).
+    pub fn end(&self) -> NaiveDateTime {
+        get_instant_of_sun_longitude(
+            self.end_year,
+            315.0,
+        )
+    }
+
+    /// The same as `start`, but calculates the
+    /// position of the sun using the given provider.
+    pub fn start_with(
+        &self,
+        provider: &dyn SunLongitudeProvider,
+    ) -> NaiveDateTime {
+        get_instant_of_sun_longitude_with(
+            provider,
+            self.start_year,
+            315.0,
+        )
+    }
+
+    /// The same as `end`, but calculates the position
+    /// of the sun using the given provider.
+    pub fn end_with(
+        &self,
+        provider: &dyn SunLongitudeProvider,
+    ) -> NaiveDateTime {
+        get_instant_of_sun_longitude_with(
+            provider,
+            self.end_year,
+            315.0,
+        )
+    }
+
+    /// The period which follows. After 九運 (Period 9),
+    /// comes 一運 (Period 1) of the next cycle.
+    pub fn next(&self) -> Self {
+        Period::from_year(self.end_year)
+    }
+
+    /// The period which precedes.
+    pub fn prev(&self) -> Self {
+        Period::from_year(self.start_year - 1)
+    }
+
+    /// Returns an iterator over periods beginning from
+    /// this period.
+    ///
+    /// Example:
+    /// ```rust
+    /// use mikaboshi::sanyuan::Period;
+    ///
+    /// let nums: Vec<usize> = Period::from_year(2000)
+    ///     .iter()
+    ///     .take(4)
+    ///     .map(|period| period.num)
+    ///     .collect();
+    /// assert_eq!(nums, vec![7, 8, 9, 1]);
+    /// ```
+    pub fn iter(&self) -> PeriodIter {
+        PeriodIter { next: *self }
+    }
+}
+
+/// An iterator over periods of 三元九運 (Sang-Yuan
+/// Jiu-Yun). It never ends.
+#[derive(Debug, Clone)]
+pub struct PeriodIter {
+    next: Period,
+}
+
+impl Iterator for PeriodIter {
+    type Item = Period;
+
+    fn next(&mut self) -> Option<Period> {
+        let period: Period = self.next;
+        self.next = period.next();
+        Some(period)
+    }
+}
+
+/// `Period` for the year which begins from 立春
+/// (Li-Chun).
+fn period_from_lichun(
+    current: NaiveDateTime,
+    lichun: NaiveDateTime,
+) -> Period {
+    Period::from_year(year_from_lichun(
+        current, lichun,
+    ))
+}
+
+/// Returns `Period` for the given date and time (UT).
+/// It belongs to the last year if it is before the
+/// instant of 立春 (Li-Chun).
+///
+/// Example:
+/// ```rust
+/// use chrono::NaiveDate;
+/// use mikaboshi::sanyuan::{get_period, Period};
+///
+/// let period: Period = get_period(NaiveDate::from_ymd(2024, 2, 3).and_hms(0, 0, 0));
+/// assert_eq!(period.num, 8);
+///
+/// let period: Period = get_period(NaiveDate::from_ymd(2024, 2, 4).and_hms(12, 0, 0));
+/// assert_eq!(period.num, 9);
+/// assert_eq!(period.start().date(), NaiveDate::from_ymd(2024, 2, 4));
+/// assert_eq!(period.end().date(), NaiveDate::from_ymd(2044, 2, 4));
+/// ```
+pub fn get_period(current: NaiveDateTime) -> Period {
+    period_from_lichun(
+        current,
+        get_instant_of_sun_longitude(
+            current.year(),
+            315.0,
+        ),
+    )
+}
+
+/// The same as `get_period`, but calculates the
+/// position of the sun using the given provider.
+pub fn get_period_with(
+    provider: &dyn SunLongitudeProvider,
+    current: NaiveDateTime,
+) -> Period {
+    period_from_lichun(
+        current,
+        get_instant_of_sun_longitude_with(
+            provider,
+            current.year(),
+            315.0,
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_period_from_year() {
+        let period = Period::from_year(1864);
+        assert_eq!(period.num, 1);
+        assert_eq!(period.yuan, Yuan::Upper);
+        assert_eq!(period.start_year, 1864);
+
+        let period = Period::from_year(1963);
+        assert_eq!(period.num, 5);
+        assert_eq!(period.yuan, Yuan::Middle);
+        assert_eq!(period.start_year, 1944);
+        assert!(period.contains_year(1963));
+        assert!(!period.contains_year(1964));
+
+        // Before 1864
+        let period = Period::from_year(1863);
+        assert_eq!(period.num, 9);
+        assert_eq!(period.yuan, Yuan::Lower);
+        assert_eq!(period.start_year, 1844);
+        assert_eq!(period.end_year, 1864);
+
+        let period = Period::from_year(1600);
+        assert_eq!(period.num, 5);
+        assert_eq!(period.start_year, 1584);
+    }
+
+    #[test]
+    fn test_period_transitions() {
+        let period = Period::from_year(1863);
+        assert_eq!(
+            period.next(),
+            Period::from_year(1864)
+        );
+        assert_eq!(period.next().prev(), period);
+
+        let periods: Vec<Period> =
+            period.iter().take(10).collect();
+        assert_eq!(periods[9].num, 9);
+        assert_eq!(periods[9].start_year, 2024);
+        for pair in periods.windows(2) {
+            assert_eq!(
+                pair[0].end_year,
+                pair[1].start_year
+            );
+        }
+    }
+}
@@ -229,6 +229,36 @@ pub fn get_shengsi_mapping(
         .collect()
 }
 
+/// Given 運盤 (Un-Pan) index, returns 生死衰旺
+/// (Sheng-Si Shuai-Wang) for the given 九星
+/// (Jiu-Xing) index. Some 九星 (Jiu-Xing) have none.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::shengsi::get_shengsi;
+///
+/// // 八運 (Period 8)
+/// assert_eq!(get_shengsi(7, 7).unwrap().key, "wang");
+/// assert_eq!(get_shengsi(7, 8).unwrap().key, "sheng");
+/// ```
+pub fn get_shengsi(
+    unpan_id: usize,
+    index: usize,
+) -> Option<&'static ShengSi<'static>> {
+    let yearly_allocs: &ShengSiYearlyAlloc =
+        &SHENG_SI_ALLOC[unpan_id];
+
+    ["sheng", "si", "shuai", "wang"]
+        .iter()
+        .find(|key| {
+            yearly_allocs
+                .accessor(key)
+                .unwrap()
+                .contains(&index)
+        })
+        .and_then(|key| SHENG_SI.get(key))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,6 +298,22 @@ mod tests {
         assert_eq!(res[7].unwrap().key, "si"); // 3
         assert_eq!(res[8].unwrap().key, "si"); // 1
     }
+
+    #[test]
+    fn test_get_shengsi() {
+        assert_eq!(
+            get_shengsi(6, 6).unwrap().key,
+            "wang"
+        );
+        assert_eq!(
+            get_shengsi(6, 5).unwrap().key,
+            "shuai"
+        );
+        assert_eq!(
+            get_shengsi(6, 7).unwrap().key,
+            "sheng"
+        );
+    }
 }
 
 // 生入 Sheng-Ru (Shēng Rù)
@@ -1,18 +1,23 @@
 //! A module for 二十四节气 (Er-Shi-Si Jie-Qi).
 //! Or, for calculating 立春 (Li-Chun).
 
-use chrono::naive::NaiveDate;
-use chrono::Datelike;
+use chrono::naive::{NaiveDate, NaiveDateTime};
+use chrono::{Datelike, Duration};
 use serde::{Deserialize, Serialize};
 use sowngwala::time::add_date;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::ops::RangeInclusive;
+use std::sync::RwLock;
 
+use crate::ephemeris::{
+    SunLongitudeProvider, DEFAULT_PROVIDER,
+};
 use crate::language::{
     Language, LanguageData, LanguageTrait,
     NameDataTrait,
 };
-use crate::utils::{
-    get_json, longitude_of_the_sun_from_generic_date,
-};
+use crate::utils::get_json;
 
 #[derive(Debug)]
 pub struct SolarTerm {
@@ -59,12 +64,203 @@ lazy_static! {
     };
 }
 
-#[allow(clippy::many_single_char_names)]
+/// Years for which instants of solar terms are cached
+/// in `TERM_TABLE`. For years out of the range, they
+/// are calculated every time.
+pub const TERM_TABLE_YEARS: RangeInclusive<i32> =
+    1800..=2200;
+
+lazy_static! {
+    /// A table caching instants (UT) of 24 solar terms
+    /// for each year. Since finding a solar term
+    /// requires calculating the position of the sun
+    /// for many times, once calculated for a year,
+    /// the results are kept here. Years are lazily
+    /// added as they are asked for, and only for
+    /// years within `TERM_TABLE_YEARS`.
+    static ref TERM_TABLE: RwLock<HashMap<i32, [NaiveDateTime; 24]>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Returns instants (UT) of 24 solar terms for the
+/// given year in the order of `SOLAR_TERMS`. For
+/// years within `TERM_TABLE_YEARS`, results are
+/// cached (using `DEFAULT_PROVIDER`).
+///
+/// Example:
+/// ```rust
+/// use chrono::naive::NaiveDateTime;
+/// use mikaboshi::solar_terms::get_term_instants;
+///
+/// // [0] 立春 (Li-Chun)
+/// let lichun: NaiveDateTime = get_term_instants(2022)[0];
+/// ```
+pub fn get_term_instants(
+    year: i32,
+) -> [NaiveDateTime; 24] {
+    if !TERM_TABLE_YEARS.contains(&year) {
+        return get_term_instants_with(
+            &DEFAULT_PROVIDER,
+            year,
+        );
+    }
+    if let Some(instants) =
+        TERM_TABLE.read().unwrap().get(&year)
+    {
+        return *instants;
+    }
+    let instants: [NaiveDateTime; 24] =
+        get_term_instants_with(
+            &DEFAULT_PROVIDER,
+            year,
+        );
+    TERM_TABLE
+        .write()
+        .unwrap()
+        .insert(year, instants);
+    instants
+}
+
+/// The same as `get_term_instants`, but calculates
+/// the position of the sun using the given provider
+/// (and nothing is cached).
+pub fn get_term_instants_with(
+    provider: &dyn SunLongitudeProvider,
+    year: i32,
+) -> [NaiveDateTime; 24] {
+    SOLAR_TERMS
+        .iter()
+        .map(|term: &SolarTerm| {
+            get_instant_of_sun_longitude_with(
+                provider,
+                year,
+                term.angle as f64,
+            )
+        })
+        .collect::<Vec<NaiveDateTime>>()
+        .try_into()
+        .unwrap()
+}
+
+/// For the given angle, finds the index in
+/// `SOLAR_TERMS`. Returns `None` if the angle is not
+/// that of any solar term.
+fn term_index_from_angle(
+    angle: f64,
+) -> Option<usize> {
+    SOLAR_TERMS
+        .iter()
+        .position(|term| term.angle as f64 == angle)
+}
+
+/// Instants of solar terms are converted to dates in
+/// the same manner as `get_date_of_sun_longitude`
+/// (which is the first date for which the longitude
+/// of the sun at 0h has passed the term).
+fn date_from_instant(
+    instant: NaiveDateTime,
+) -> NaiveDate {
+    add_date(
+        (instant - Duration::seconds(1)).date(),
+        1,
+    )
+}
+
+/// Finds the last solar term for the given date and
+/// time (UT), and returns the longitude of the term
+/// and its instant. Instants of solar terms are looked
+/// up from `TERM_TABLE`.
+pub fn get_last_term_instant(
+    datetime: NaiveDateTime,
+) -> (f64, NaiveDateTime) {
+    let year: i32 = datetime.year();
+    [year - 1, year]
+        .iter()
+        .flat_map(|y| {
+            SOLAR_TERMS
+                .iter()
+                .zip(get_term_instants(*y).to_vec())
+        })
+        .filter(|(_, instant)| *instant <= datetime)
+        .max_by_key(|(_, instant)| *instant)
+        .map(|(term, instant)| {
+            (term.angle as f64, instant)
+        })
+        .unwrap()
+}
+
+/// The same as `get_last_term_instant`, but
+/// calculates the position of the sun using the given
+/// provider.
+pub fn get_last_term_instant_with(
+    provider: &dyn SunLongitudeProvider,
+    datetime: NaiveDateTime,
+) -> (f64, NaiveDateTime) {
+    let lng: f64 =
+        provider.longitude_of_the_sun(datetime);
+    let target: f64 = (lng / 15.0).floor() * 15.0;
+    let instant = get_instant_of_sun_longitude_with(
+        provider,
+        datetime.year(),
+        target,
+    );
+    // Early in January, the last term could be the
+    // one in the previous year.
+    if instant > datetime {
+        (
+            target,
+            get_instant_of_sun_longitude_with(
+                provider,
+                datetime.year() - 1,
+                target,
+            ),
+        )
+    } else {
+        (target, instant)
+    }
+}
+
+/// Finds the last solar term for the given date, and
+/// returns the longitude of the term and its date.
+/// Instants of solar terms are looked up from
+/// `TERM_TABLE`.
 pub fn get_last_term(
     date: NaiveDate,
+) -> (f64, NaiveDate) {
+    if !TERM_TABLE_YEARS.contains(&date.year()) {
+        return get_last_term_with(
+            &DEFAULT_PROVIDER,
+            date,
+        );
+    }
+    let year: i32 = date.year();
+    [year - 1, year]
+        .iter()
+        .flat_map(|y| {
+            SOLAR_TERMS
+                .iter()
+                .zip(get_term_instants(*y).to_vec())
+        })
+        .map(|(term, instant)| {
+            (
+                term.angle as f64,
+                date_from_instant(instant),
+            )
+        })
+        .filter(|(_, term_date)| *term_date <= date)
+        .max_by_key(|(_, term_date)| *term_date)
+        .unwrap()
+}
+
+/// The same as `get_last_term`, but calculates the
+/// position of the sun using the given provider.
+#[allow(clippy::many_single_char_names)]
+pub fn get_last_term_with(
+    provider: &dyn SunLongitudeProvider,
+    date: NaiveDate,
 ) -> (f64, NaiveDate) {
     let lng_0: f64 =
-        longitude_of_the_sun_from_generic_date(date);
+        provider.longitude_of_the_sun_from_date(date);
     // For the unit of 15, we want the last term.
     // Ex.
     //   317.435511 --> 315.0
@@ -81,10 +277,8 @@ pub fn get_last_term(
 
     // Go back by one day a time.
     while term.is_none() {
-        let lng: f64 =
-            longitude_of_the_sun_from_generic_date(
-                next,
-            );
+        let lng: f64 = provider
+            .longitude_of_the_sun_from_date(next);
         // See if the target falls in the current date.
         if lng <= target && lng > (target - 1.0) {
             term = prev;
@@ -113,16 +307,215 @@ pub fn get_last_term(
 ///     ))
 /// }
 /// ```
-#[allow(clippy::many_single_char_names)]
 pub fn get_lichun(year: i32) -> NaiveDate {
+    get_date_of_sun_longitude(year, 315.0)
+}
+
+/// In 風水 (Feng-Shui), the year begins from 立春
+/// (Li-Chun). Returns the year which the given date and
+/// time belongs to, where `lichun` is the instant of
+/// 立春 (Li-Chun) in the same calendar year. It belongs
+/// to the last year if it is before `lichun`.
+///
+/// Example:
+/// ```rust
+/// use chrono::NaiveDate;
+/// use mikaboshi::solar_terms::year_from_lichun;
+///
+/// let lichun = NaiveDate::from_ymd(2024, 2, 4).and_hms(8, 27, 0);
+/// let before = NaiveDate::from_ymd(2024, 2, 4).and_hms(8, 0, 0);
+/// assert_eq!(year_from_lichun(before, lichun), 2023);
+/// assert_eq!(year_from_lichun(lichun, lichun), 2024);
+/// ```
+pub fn year_from_lichun(
+    datetime: NaiveDateTime,
+    lichun: NaiveDateTime,
+) -> i32 {
+    if datetime < lichun {
+        datetime.year() - 1
+    } else {
+        datetime.year()
+    }
+}
+
+/// The same as `get_lichun`, but calculates the
+/// position of the sun using the given provider.
+#[allow(clippy::many_single_char_names)]
+pub fn get_lichun_with(
+    provider: &dyn SunLongitudeProvider,
+    year: i32,
+) -> NaiveDate {
     let date = NaiveDate::from_ymd(year, 2, 6);
-    let (_lng, lichun) = get_last_term(date);
+    let (_lng, lichun) =
+        get_last_term_with(provider, date);
     lichun
 }
 
+/// Returns the date on which the sun reaches the given
+/// ecliptic longitude (`angle`) within the given year.
+/// Just like `get_last_term`, it walks one day at a
+/// time, and returns the first date for which the
+/// longitude of the sun has passed `angle`. The search
+/// starts a few days before the rough estimate for the
+/// date (the sun moves roughly 1 degree a day, and is
+/// at about 280 degrees on January 1st).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::solar_terms::get_date_of_sun_longitude;
+///
+/// // 春分 (Chun-Fen)
+/// let chunfen = get_date_of_sun_longitude(2022, 0.0);
+/// ```
+pub fn get_date_of_sun_longitude(
+    year: i32,
+    angle: f64,
+) -> NaiveDate {
+    match term_index_from_angle(angle) {
+        Some(index)
+            if TERM_TABLE_YEARS.contains(&year) =>
+        {
+            date_from_instant(
+                get_term_instants(year)[index],
+            )
+        }
+        _ => get_date_of_sun_longitude_with(
+            &DEFAULT_PROVIDER,
+            year,
+            angle,
+        ),
+    }
+}
+
+/// The same as `get_date_of_sun_longitude`, but
+/// calculates the position of the sun using the given
+/// provider.
+pub fn get_date_of_sun_longitude_with(
+    provider: &dyn SunLongitudeProvider,
+    year: i32,
+    angle: f64,
+) -> NaiveDate {
+    let offset: f64 =
+        (angle - 280.0).rem_euclid(360.0) / 360.0
+            * 365.2422;
+
+    let mut next = add_date(
+        NaiveDate::from_ymd(year, 1, 1),
+        offset.floor() as i64 - 5,
+    );
+
+    let limit = 400;
+    let mut cnt = 0;
+
+    // When the longitude of the sun has already
+    // passed the target (for the rough estimate may
+    // be off), go back by one day a time.
+    while longitude_diff(
+        provider.longitude_of_the_sun_from_date(next),
+        angle,
+    ) > 0.0
+    {
+        if cnt > limit {
+            panic!("Iteration reached: {}", limit);
+        }
+        next = add_date(next, -1_i64);
+        cnt += 1;
+    }
+
+    // Go forward by one day a time, until the
+    // longitude of the sun passes the target.
+    // Difference is taken in the range of -180 to
+    // 180 so that it works for 0 degree as well.
+    loop {
+        if cnt > limit {
+            panic!("Iteration reached: {}", limit);
+        }
+        let lng: f64 = provider
+            .longitude_of_the_sun_from_date(next);
+        if longitude_diff(lng, angle) > 0.0 {
+            return next;
+        }
+        next = add_date(next, 1_i64);
+        cnt += 1;
+    }
+}
+
+/// Returns the date and time (UT) on which the sun
+/// reaches the given ecliptic longitude (`angle`)
+/// within the given year.
+///
+/// Example:
+/// ```rust
+/// use chrono::naive::NaiveDateTime;
+/// use mikaboshi::solar_terms::get_instant_of_sun_longitude;
+///
+/// // 立春 (Li-Chun)
+/// let lichun: NaiveDateTime =
+///     get_instant_of_sun_longitude(2022, 315.0);
+/// ```
+pub fn get_instant_of_sun_longitude(
+    year: i32,
+    angle: f64,
+) -> NaiveDateTime {
+    match term_index_from_angle(angle) {
+        Some(index)
+            if TERM_TABLE_YEARS.contains(&year) =>
+        {
+            get_term_instants(year)[index]
+        }
+        _ => get_instant_of_sun_longitude_with(
+            &DEFAULT_PROVIDER,
+            year,
+            angle,
+        ),
+    }
+}
+
+/// The same as `get_instant_of_sun_longitude`, but
+/// calculates the position of the sun using the given
+/// provider.
+pub fn get_instant_of_sun_longitude_with(
+    provider: &dyn SunLongitudeProvider,
+    year: i32,
+    angle: f64,
+) -> NaiveDateTime {
+    // The sun passes `angle` sometime during the day
+    // before the date found.
+    let date: NaiveDate =
+        get_date_of_sun_longitude_with(
+            provider, year, angle,
+        );
+    let mut hi: NaiveDateTime = date.and_hms(0, 0, 0);
+    let mut lo: NaiveDateTime =
+        add_date(date, -1).and_hms(0, 0, 0);
+
+    // Bisect until it is narrowed down to a second.
+    while hi - lo > Duration::seconds(1) {
+        let mid: NaiveDateTime = lo
+            + Duration::seconds(
+                (hi - lo).num_seconds() / 2,
+            );
+        let lng: f64 =
+            provider.longitude_of_the_sun(mid);
+        if longitude_diff(lng, angle) >= 0.0 {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    hi
+}
+
+/// Difference between the 2 longitudes, normalized
+/// to fit within the range of -180 to 180.
+fn longitude_diff(lng: f64, angle: f64) -> f64 {
+    (lng - angle + 540.0).rem_euclid(360.0) - 180.0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Timelike;
 
     #[test]
     fn test_get_last_term() {
@@ -134,4 +527,120 @@ mod tests {
         assert_eq!(term.month(), 2);
         assert_eq!(term.day(), 4);
     }
+
+    #[test]
+    fn test_get_date_of_sun_longitude() {
+        // 春分 (Chun-Fen)
+        assert_eq!(
+            get_date_of_sun_longitude(2022, 0.0),
+            NaiveDate::from_ymd(2022, 3, 21)
+        );
+        // 立春 (Li-Chun)
+        assert_eq!(
+            get_date_of_sun_longitude(2022, 315.0),
+            get_lichun(2022)
+        );
+    }
+
+    #[test]
+    fn test_get_instant_of_sun_longitude() {
+        // 立春 (Li-Chun) in 2022 is 20:51 (UT) on
+        // February 3rd.
+        let instant =
+            get_instant_of_sun_longitude(2022, 315.0);
+        assert_eq!(
+            instant.date(),
+            NaiveDate::from_ymd(2022, 2, 3)
+        );
+        assert_eq!(instant.hour(), 20);
+    }
+
+    /// A test double for `SunLongitudeProvider`
+    /// where the sun moves exactly 1 degree a day,
+    /// being at 0 degree on January 1st, 2000.
+    struct OneDegreeADay;
+
+    impl SunLongitudeProvider for OneDegreeADay {
+        fn longitude_of_the_sun(
+            &self,
+            datetime: NaiveDateTime,
+        ) -> f64 {
+            let epoch =
+                NaiveDate::from_ymd(2000, 1, 1)
+                    .and_hms(0, 0, 0);
+            let days = (datetime - epoch)
+                .num_seconds()
+                as f64
+                / 86400.0;
+            days.rem_euclid(360.0)
+        }
+    }
+
+    #[test]
+    fn test_get_date_of_sun_longitude_with() {
+        // 2000-01-01 + 100 days
+        assert_eq!(
+            get_date_of_sun_longitude_with(
+                &OneDegreeADay,
+                2000,
+                99.5
+            ),
+            NaiveDate::from_ymd(2000, 4, 10)
+        );
+        assert_eq!(
+            get_instant_of_sun_longitude_with(
+                &OneDegreeADay,
+                2000,
+                99.5
+            ),
+            NaiveDate::from_ymd(2000, 4, 9)
+                .and_hms(12, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_get_term_instants() {
+        let instants = get_term_instants(2022);
+        assert_eq!(
+            instants[0],
+            get_instant_of_sun_longitude_with(
+                &DEFAULT_PROVIDER,
+                2022,
+                315.0
+            )
+        );
+        // Cached results are the same.
+        assert_eq!(get_term_instants(2022), instants);
+    }
+
+    #[test]
+    fn test_term_table_is_transparent() {
+        // Within the range, and out of the range.
+        for year in [1900, 2022, 2200, 1700, 2300] {
+            assert_eq!(
+                get_lichun(year),
+                get_lichun_with(
+                    &DEFAULT_PROVIDER,
+                    year
+                )
+            );
+            let date =
+                NaiveDate::from_ymd(year, 1, 3);
+            assert_eq!(
+                get_last_term(date),
+                get_last_term_with(
+                    &DEFAULT_PROVIDER,
+                    date
+                )
+            );
+            let datetime = date.and_hms(12, 0, 0);
+            assert_eq!(
+                get_last_term_instant(datetime),
+                get_last_term_instant_with(
+                    &DEFAULT_PROVIDER,
+                    datetime
+                )
+            );
+        }
+    }
 }
@@ -0,0 +1,288 @@
+//! In 玄空飞星風水 (Xuan-Kong Fei-Xing Feng-Shui),
+//! each palace is read by the combination of its
+//! 山星 (Shan-Xing) and 向星 (Xiang-Xing). For
+//! instance, 1-4 is known as 文昌 (Wen-Chang) which
+//! brings literary success, whereas 2-5 brings
+//! serious illness. There are 81 (9 x 9) pairs, and
+//! this module provides the meanings for them.
+//!
+//! However, the same pair may work differently
+//! depending on the timeliness of the stars. When
+//! both stars are in 生 (Sheng) or 旺 (Wang) for the
+//! current period, the pair works for the better, and
+//! when both are in 衰 (Shuai) or 死 (Si), it works
+//! for the worse.
+
+use serde::{Deserialize, Serialize};
+
+use crate::jiuxing::{
+    NatalChart, LO_SHU_FLIGHT_PATH,
+};
+use crate::language::{
+    Language, LanguageData, LanguageTrait,
+    NameDataTrait,
+};
+use crate::shengsi::{get_shengsi, ShengSi};
+use crate::utils::get_json;
+
+/// Whether the pair is auspicious or not.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Fortune {
+    Good,
+    Mixed,
+    Bad,
+}
+
+/// A struct representing a pair of 山星 (Shan-Xing)
+/// and 向星 (Xiang-Xing). `mountain` and `facing` are
+/// 九星 (Jiu-Xing) numbers (from 1 to 9), not indexes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarPair {
+    pub id: u8,
+    pub mountain: usize,
+    pub facing: usize,
+    pub name: Language,
+    pub fortune: Fortune,
+}
+
+/// A temporary struct for loading JSON data when
+/// defining a static const `STAR_PAIRS`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarPairRawData {
+    pub id: u8,
+    pub mountain: usize,
+    pub facing: usize,
+    pub name: LanguageData,
+    pub fortune: Fortune,
+}
+
+/// A struct representing the reading for a palace.
+/// `direction` is that of the palace ("" being the
+/// center), and `mountain` and `facing` are 九星
+/// (Jiu-Xing) indexes. `fortune` is that of `pair`
+/// adjusted by the timeliness of the stars.
+#[derive(Debug, Clone, Serialize)]
+pub struct StarPairReading {
+    pub direction: &'static str,
+    pub mountain: usize,
+    pub facing: usize,
+    pub pair: &'static StarPair,
+    pub mountain_shengsi:
+        Option<&'static ShengSi<'static>>,
+    pub facing_shengsi:
+        Option<&'static ShengSi<'static>>,
+    pub fortune: Fortune,
+}
+
+impl LanguageTrait for StarPair {
+    fn name(&self) -> Box<Language> {
+        Box::new(self.name.clone())
+    }
+}
+
+impl NameDataTrait for StarPairRawData {
+    fn name(&self) -> Box<LanguageData> {
+        Box::new(self.name.clone())
+    }
+}
+
+lazy_static! {
+    /// A static vector with 81 items, each represents
+    /// a pair of 山星 (Shan-Xing) and 向星
+    /// (Xiang-Xing). They are sorted by 山星
+    /// (Shan-Xing), and then by 向星 (Xiang-Xing).
+    ///
+    /// For attributes details stored in the vector is
+    /// found in JSON file:
+    /// `src/json/star_pairs.json`
+    pub static ref STAR_PAIRS: Vec<StarPair> = {
+        let json = &include_str!("../json/star_pairs.json");
+        let data: Vec<StarPairRawData> =
+            get_json::<StarPairRawData>(json);
+        data.iter()
+            .map(|item| StarPair {
+                id: item.id,
+                mountain: item.mountain,
+                facing: item.facing,
+                name: item.language_from_data(),
+                fortune: item.fortune,
+            })
+            .collect()
+    };
+}
+
+/// Returns `StarPair` for the given 山星 (Shan-Xing)
+/// index and 向星 (Xiang-Xing) index.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::star_pairs::{get_star_pair, Fortune};
+///
+/// // 一白水星 (1 White) and 四緑木星 (4 Green)
+/// let pair = get_star_pair(0, 3);
+/// assert_eq!(pair.name.en, "literary success");
+/// assert_eq!(pair.fortune, Fortune::Good);
+/// ```
+pub fn get_star_pair(
+    mountain: usize,
+    facing: usize,
+) -> &'static StarPair {
+    &STAR_PAIRS[mountain * 9 + facing]
+}
+
+/// Whether the star is timely (1), untimely (-1), or
+/// neither (0).
+fn timeliness(shengsi: Option<&ShengSi>) -> i32 {
+    match shengsi.map(|s| s.key) {
+        Some("sheng") | Some("wang") => 1,
+        Some("shuai") | Some("si") => -1,
+        _ => 0,
+    }
+}
+
+/// Adjusts the fortune of the pair by timeliness of
+/// the 2 stars. It gets better by a grade when both
+/// are timely, and worse when both are untimely.
+pub(crate) fn adjust_fortune(
+    fortune: Fortune,
+    mountain: Option<&ShengSi>,
+    facing: Option<&ShengSi>,
+) -> Fortune {
+    use Fortune::*;
+    match (
+        timeliness(mountain) + timeliness(facing),
+        fortune,
+    ) {
+        (2, Mixed) | (2, Good) => Good,
+        (2, Bad) => Mixed,
+        (-2, Good) => Mixed,
+        (-2, Mixed) | (-2, Bad) => Bad,
+        (_, fortune) => fortune,
+    }
+}
+
+/// Returns readings for all the palaces of the given
+/// `NatalChart` (in the order of `LO_SHU_FLIGHT_PATH`).
+/// Timeliness of the stars is that of the period of
+/// the chart (the center of 運盤 (Un-Pan)).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::jiuxing::get_natal_chart;
+/// use mikaboshi::star_pairs::{get_star_pair_readings, StarPairReading};
+///
+/// // 八運 (Period 8), 丑山未向
+/// let chart = get_natal_chart(7, "sw", 1, "n").unwrap();
+/// let readings: Vec<StarPairReading> = get_star_pair_readings(&chart);
+/// for reading in readings.iter() {
+///     println!("{} {}", reading.direction, reading.pair.name.en);
+/// }
+/// ```
+pub fn get_star_pair_readings(
+    chart: &NatalChart,
+) -> Vec<StarPairReading> {
+    let unpan_id: usize = chart.period.center();
+
+    LO_SHU_FLIGHT_PATH
+        .iter()
+        .filter_map(|&direction| {
+            let mountain =
+                chart.mountain.get(direction)?;
+            let facing =
+                chart.facing.get(direction)?;
+            let pair =
+                get_star_pair(mountain, facing);
+            let mountain_shengsi =
+                get_shengsi(unpan_id, mountain);
+            let facing_shengsi =
+                get_shengsi(unpan_id, facing);
+            Some(StarPairReading {
+                direction,
+                mountain,
+                facing,
+                pair,
+                mountain_shengsi,
+                facing_shengsi,
+                fortune: adjust_fortune(
+                    pair.fortune,
+                    mountain_shengsi,
+                    facing_shengsi,
+                ),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jiuxing::get_natal_chart;
+
+    #[test]
+    fn test_constant_star_pairs() {
+        assert_eq!(STAR_PAIRS.len(), 81);
+        for (i, pair) in STAR_PAIRS.iter().enumerate()
+        {
+            assert_eq!(pair.mountain, i / 9 + 1);
+            assert_eq!(pair.facing, i % 9 + 1);
+        }
+        assert_eq!(
+            get_star_pair(1, 4).alphabet(),
+            "重病"
+        );
+        assert_eq!(
+            get_star_pair(4, 1).alphabet(),
+            "重病"
+        );
+    }
+
+    #[test]
+    fn test_adjust_fortune() {
+        let wang =
+            crate::shengsi::SHENG_SI.get("wang");
+        let si = crate::shengsi::SHENG_SI.get("si");
+        assert_eq!(
+            adjust_fortune(
+                Fortune::Mixed,
+                wang,
+                wang
+            ),
+            Fortune::Good
+        );
+        assert_eq!(
+            adjust_fortune(Fortune::Good, si, si),
+            Fortune::Mixed
+        );
+        assert_eq!(
+            adjust_fortune(Fortune::Bad, wang, si),
+            Fortune::Bad
+        );
+    }
+
+    #[test]
+    fn test_get_star_pair_readings() {
+        // 八運 (Period 8), 丑山未向
+        let chart =
+            get_natal_chart(7, "sw", 1, "n").unwrap();
+        let readings = get_star_pair_readings(&chart);
+        assert_eq!(readings.len(), 9);
+
+        // 山星 (Shan-Xing) 8 and 向星 (Xiang-Xing) 2
+        // at the sitting.
+        let ne = readings
+            .iter()
+            .find(|r| r.direction == "ne")
+            .unwrap();
+        assert_eq!((ne.mountain, ne.facing), (7, 1));
+        assert_eq!(ne.pair.fortune, Fortune::Good);
+    }
+}
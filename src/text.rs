@@ -0,0 +1,479 @@
+//! A module for rendering charts as plain text (with
+//! box-drawing characters) for terminals and reports.
+//!
+//! (1) `BaziText` --> 八字 (Bazi) in the traditional
+//! four columns with 藏干 (Cang-Gan), or "Hidden
+//! Stems".
+//! (2) `ChartText` --> 3x3 flying-star chart.
+//! (3) `StarGridText` --> 3x3 chart for any `StarGrid`
+//! (for instance, 年紫白 (Nian Zi-Bai)).
+//!
+//! All of them implement `Display`. CJK characters and
+//! full-width numerals are assumed to take 2 columns in
+//! terminals.
+
+use std::fmt;
+
+use crate::compass::{
+    get_direction_name, DIRECTION_POSITIONS_IN_CHART,
+};
+use crate::ganzhi::{Bazi, GanZhi};
+use crate::jiuxing::{NatalChart, StarGrid};
+use crate::language::LanguageKind;
+
+/// Name of the direction ("" being the center).
+pub(crate) fn direction_label(
+    dir: &str,
+    language: LanguageKind,
+) -> String {
+    get_direction_name(dir)
+        .map(|name| {
+            name.name.text(language).to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether the south or the north comes to the top of
+/// 3x3 charts in text. Traditionally, charts are drawn
+/// with the south up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    SouthUp,
+    NorthUp,
+}
+
+impl Orientation {
+    /// Key for `DIRECTION_POSITIONS_IN_CHART`.
+    fn layout(&self) -> &'static str {
+        match self {
+            Orientation::SouthUp => "s",
+            Orientation::NorthUp => "n",
+        }
+    }
+}
+
+/// Options for rendering in text. `language` is used
+/// for labels, and `orientation` is for 3x3 charts (it
+/// has no effect on 八字 (Bazi)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextOptions {
+    pub language: LanguageKind,
+    pub orientation: Orientation,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions {
+            language: LanguageKind::ZhTw,
+            orientation: Orientation::SouthUp,
+        }
+    }
+}
+
+/// Width of the text in terminals where CJK
+/// characters (and full-width numerals) take 2
+/// columns.
+fn text_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| if c >= '\u{2E80}' { 2 } else { 1 })
+        .sum()
+}
+
+fn center(text: &str, width: usize) -> String {
+    let rest: usize =
+        width.saturating_sub(text_width(text));
+    let left: usize = rest / 2;
+    format!(
+        "{}{}{}",
+        " ".repeat(left),
+        text,
+        " ".repeat(rest - left)
+    )
+}
+
+/// Full-width numeral (from 1 to 9) for the given
+/// 九星 (Jiu-Xing) index.
+fn large_numeral(index: usize) -> String {
+    char::from_u32(0xFF11 + index as u32)
+        .map(|c| c.to_string())
+        .unwrap_or_default()
+}
+
+fn rule(
+    [left, middle, right]: [&str; 3],
+    columns: usize,
+    width: usize,
+) -> String {
+    format!(
+        "{}{}{}",
+        left,
+        vec!["─".repeat(width); columns].join(middle),
+        right
+    )
+}
+
+/// Renders a table where `sections` are divided by
+/// rules. Each section has lines, and each line has a
+/// label for every column. Columns share the same
+/// width so that 3x3 charts come out square.
+fn table(sections: &[Vec<Vec<String>>]) -> String {
+    let columns: usize = sections
+        .iter()
+        .flatten()
+        .map(|line| line.len())
+        .max()
+        .unwrap_or(0);
+    let width: usize = sections
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|label| text_width(label))
+        .max()
+        .unwrap_or(0)
+        + 2;
+
+    let mut lines: Vec<String> =
+        vec![rule(["┌", "┬", "┐"], columns, width)];
+    for (i, section) in sections.iter().enumerate() {
+        if i > 0 {
+            lines.push(rule(
+                ["├", "┼", "┤"],
+                columns,
+                width,
+            ));
+        }
+        for line in section {
+            let cells: Vec<String> = line
+                .iter()
+                .map(|label| center(label, width))
+                .collect();
+            lines.push(format!(
+                "│{}│",
+                cells.join("│")
+            ));
+        }
+    }
+    lines.push(rule(["└", "┴", "┘"], columns, width));
+    lines.join("\n")
+}
+
+/// Renders 3x3 boxes in text where `cell` returns the
+/// labels (middle, bottom) for the direction. The
+/// direction comes on the top.
+fn text_boxes<F>(
+    options: &TextOptions,
+    cell: F,
+) -> Option<String>
+where
+    F: Fn(&str) -> Option<[String; 2]>,
+{
+    let positions: &[&str; 9] =
+        DIRECTION_POSITIONS_IN_CHART
+            .get(options.orientation.layout())?;
+    let sections = positions
+        .chunks(3)
+        .map(|row| {
+            let cells = row
+                .iter()
+                .map(|dir| {
+                    let [middle, bottom] = cell(dir)?;
+                    Some([
+                        direction_label(
+                            dir,
+                            options.language,
+                        ),
+                        middle,
+                        bottom,
+                    ])
+                })
+                .collect::<Option<Vec<[String; 3]>>>(
+                )?;
+            Some(
+                (0..3)
+                    .map(|k| {
+                        cells
+                            .iter()
+                            .map(|labels| {
+                                labels[k].clone()
+                            })
+                            .collect()
+                    })
+                    .collect(),
+            )
+        })
+        .collect::<Option<Vec<Vec<Vec<String>>>>>()?;
+    Some(table(&sections))
+}
+
+/// Labels for 時柱 (hour), 日柱 (day), 月柱 (month),
+/// and 年柱 (year).
+fn pillar_labels(
+    language: LanguageKind,
+) -> [&'static str; 4] {
+    match language {
+        LanguageKind::En => {
+            ["Hour", "Day", "Month", "Year"]
+        }
+        LanguageKind::Ja | LanguageKind::ZhTw => {
+            ["時柱", "日柱", "月柱", "年柱"]
+        }
+        LanguageKind::ZhCn => {
+            ["时柱", "日柱", "月柱", "年柱"]
+        }
+        LanguageKind::Vi => {
+            ["Giờ", "Ngày", "Tháng", "Năm"]
+        }
+    }
+}
+
+/// 八字 (Bazi) in text with the traditional four
+/// columns: 時柱 (hour), 日柱 (day), 月柱 (month), and
+/// 年柱 (year) from the left. Each column has the stem
+/// and the branch, followed by 藏干 (Cang-Gan), or
+/// "Hidden Stems" of the branch.
+///
+/// Example:
+/// ```rust
+/// use chrono::NaiveTime;
+/// use sowngwala::time::build_utc;
+/// use mikaboshi::ganzhi::Bazi;
+/// use mikaboshi::text::{BaziText, TextOptions};
+///
+/// let bazi = Bazi::from_utc(
+///     build_utc(2021, 7, 6, 5, 54, 34, 0),
+///     NaiveTime::from_hms(14, 57, 17),
+/// );
+/// let text: String = BaziText::new(&bazi, TextOptions::default()).to_string();
+/// let lines: Vec<&str> = text.lines().collect();
+/// assert_eq!(lines[1], "│ 時柱 │ 日柱 │ 月柱 │ 年柱 │");
+/// assert_eq!(lines[3], "│  癸  │  乙  │  甲  │  辛  │");
+/// assert_eq!(lines[4], "│  未  │  卯  │  午  │  丑  │");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BaziText<'a> {
+    pub bazi: &'a Bazi<'a>,
+    pub options: TextOptions,
+}
+
+impl<'a> BaziText<'a> {
+    pub fn new(
+        bazi: &'a Bazi<'a>,
+        options: TextOptions,
+    ) -> Self {
+        BaziText { bazi, options }
+    }
+}
+
+impl fmt::Display for BaziText<'_> {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        let language: LanguageKind =
+            self.options.language;
+        let pillars: [&GanZhi; 4] = [
+            &self.bazi.hour,
+            &self.bazi.day,
+            &self.bazi.month,
+            &self.bazi.year,
+        ];
+        let hidden: Vec<Vec<String>> = pillars
+            .iter()
+            .map(|pillar| {
+                pillar
+                    .branch
+                    .hidden_stems()
+                    .iter()
+                    .map(|stem| {
+                        stem.name
+                            .text(language)
+                            .to_string()
+                    })
+                    .collect()
+            })
+            .collect();
+        let depth: usize = hidden
+            .iter()
+            .map(|stems| stems.len())
+            .max()
+            .unwrap_or(0);
+
+        let sections: Vec<Vec<Vec<String>>> = vec![
+            vec![pillar_labels(language)
+                .iter()
+                .map(|label| label.to_string())
+                .collect()],
+            vec![
+                pillars
+                    .iter()
+                    .map(|pillar| {
+                        pillar
+                            .stem
+                            .name
+                            .text(language)
+                            .to_string()
+                    })
+                    .collect(),
+                pillars
+                    .iter()
+                    .map(|pillar| {
+                        pillar
+                            .branch
+                            .name
+                            .text(language)
+                            .to_string()
+                    })
+                    .collect(),
+            ],
+            (0..depth)
+                .map(|k| {
+                    hidden
+                        .iter()
+                        .map(|stems| {
+                            stems
+                                .get(k)
+                                .cloned()
+                                .unwrap_or_default()
+                        })
+                        .collect()
+                })
+                .collect(),
+        ];
+        write!(f, "{}", table(&sections))
+    }
+}
+
+/// `NatalChart` in text as 3x3 boxes. Each box has the
+/// direction on the top, 山星 (Shan-Xing) and 向星
+/// (Xiang-Xing) in the middle (in large numerals), and
+/// 運盤星 (Un-Pan Xing) on the bottom (in small
+/// numerals).
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::jiuxing::get_natal_chart;
+/// use mikaboshi::text::{ChartText, TextOptions};
+///
+/// // 八運 (Period 8), 子山午向
+/// let chart = get_natal_chart(7, "s", 2, "n").unwrap();
+/// let text: String = ChartText::new(&chart, TextOptions::default()).to_string();
+/// let lines: Vec<&str> = text.lines().collect();
+/// assert_eq!(lines[1], "│  東南  │   南   │  西南  │");
+/// assert_eq!(lines[2], "│ ３  ４ │ ８  ８ │ １  ６ │");
+/// assert_eq!(lines[3], "│   7    │   3    │   5    │");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ChartText<'a> {
+    pub chart: &'a NatalChart,
+    pub options: TextOptions,
+}
+
+impl<'a> ChartText<'a> {
+    pub fn new(
+        chart: &'a NatalChart,
+        options: TextOptions,
+    ) -> Self {
+        ChartText { chart, options }
+    }
+}
+
+impl fmt::Display for ChartText<'_> {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        let text = text_boxes(&self.options, |dir| {
+            let (period, mountain, facing) =
+                self.chart.palace(dir)?;
+            Some([
+                format!(
+                    "{}  {}",
+                    large_numeral(mountain),
+                    large_numeral(facing)
+                ),
+                (period + 1).to_string(),
+            ])
+        })
+        .ok_or(fmt::Error)?;
+        write!(f, "{}", text)
+    }
+}
+
+/// `StarGrid` in text as 3x3 boxes. Each box has the
+/// direction on the top, and the star in the middle
+/// (in large numerals).
+#[derive(Debug, Clone, Copy)]
+pub struct StarGridText<'a> {
+    pub grid: &'a StarGrid,
+    pub options: TextOptions,
+}
+
+impl<'a> StarGridText<'a> {
+    pub fn new(
+        grid: &'a StarGrid,
+        options: TextOptions,
+    ) -> Self {
+        StarGridText { grid, options }
+    }
+}
+
+impl fmt::Display for StarGridText<'_> {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        let text = text_boxes(&self.options, |dir| {
+            Some([
+                large_numeral(self.grid.get(dir)?),
+                String::new(),
+            ])
+        })
+        .ok_or(fmt::Error)?;
+        write!(f, "{}", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jiuxing::{
+        fly_stars, get_natal_chart, Flight,
+    };
+
+    #[test]
+    fn test_chart_text_orientation() {
+        let chart =
+            get_natal_chart(7, "s", 2, "n").unwrap();
+        let options = TextOptions {
+            language: LanguageKind::En,
+            orientation: Orientation::NorthUp,
+        };
+        let text = ChartText::new(&chart, options)
+            .to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        // 3 rules and 3 lines for each row, with the
+        // north on the top.
+        assert_eq!(lines.len(), 13);
+        assert!(lines[1].contains(" north "));
+        assert!(lines[9].contains(" south "));
+        let width = text_width(lines[0]);
+        for line in &lines {
+            assert_eq!(text_width(line), width);
+        }
+    }
+
+    #[test]
+    fn test_star_grid_text() {
+        // 年紫白 (Nian Zi-Bai) for 2024
+        let grid = fly_stars(2, "n", Flight::Forward)
+            .unwrap();
+        let text = StarGridText::new(
+            &grid,
+            TextOptions::default(),
+        )
+        .to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        // 三碧木星 (3 Jade) in the center
+        assert!(lines[6].contains("３"));
+    }
+}
@@ -1,10 +1,13 @@
+use chrono::naive::NaiveDate;
 use chrono::Datelike;
 
 // use core::iter::FromIterator;
 // use std::iter::FromIterator;
 use serde::Deserialize;
 
-use sowngwala::sun::ecliptic_position_of_the_sun_from_generic_date;
+use crate::ephemeris::{
+    SunLongitudeProvider, DEFAULT_PROVIDER,
+};
 
 pub fn get_json<'a, T: Deserialize<'a>>(
     json: &'a str,
@@ -51,6 +54,8 @@ pub fn make_positive(
     })
 }
 
+/// Returns the longitude of the sun for the given date
+/// using `DEFAULT_PROVIDER` (see `src/ephemeris.rs`).
 pub fn longitude_of_the_sun_from_generic_date<T>(
     date: T,
 ) -> f64
@@ -60,8 +65,11 @@ where
     T: std::fmt::Debug,
     T: std::fmt::Display,
 {
-    ecliptic_position_of_the_sun_from_generic_date(
-        date,
+    DEFAULT_PROVIDER.longitude_of_the_sun_from_date(
+        NaiveDate::from_ymd(
+            date.year(),
+            date.month(),
+            date.day(),
+        ),
     )
-    .lng
 }
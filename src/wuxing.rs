@@ -52,3 +52,75 @@ lazy_static! {
             .collect()
     };
 }
+
+/// Relationship between 2 of 五行 (Wu-Xing). For 相生
+/// (Xiang-Sheng), wood generates fire, fire generates
+/// earth, earth generates metal, metal generates water,
+/// and water generates wood. For 相剋 (Xiang-Ke), wood
+/// overcomes earth, earth overcomes water, water
+/// overcomes fire, fire overcomes metal, and metal
+/// overcomes wood.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum WuXingRelation {
+    Same,        // 比和
+    Generates,   // 生
+    GeneratedBy, // 被生
+    Overcomes,   // 剋
+    OvercomeBy,  // 被剋
+}
+
+impl WuXingRelation {
+    /// 比和 (Bi-He) and 相生 (Xiang-Sheng) are
+    /// considered compatible.
+    pub fn is_compatible(&self) -> bool {
+        matches!(
+            self,
+            WuXingRelation::Same
+                | WuXingRelation::Generates
+                | WuXingRelation::GeneratedBy
+        )
+    }
+}
+
+/// Returns the index of the given 五行 (Wu-Xing) in
+/// `WU_XING`.
+pub fn get_wuxing_index(
+    element: &WuXing,
+) -> Option<usize> {
+    WU_XING
+        .iter()
+        .position(|w| w.name.en == element.name.en)
+}
+
+/// Returns the relationship of `a` to `b` where both
+/// are the indexes for `WU_XING`.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::wuxing::{get_wuxing_relation, WuXingRelation};
+///
+/// // Wood generates fire.
+/// assert_eq!(get_wuxing_relation(0, 1), WuXingRelation::Generates);
+/// // Wood overcomes earth.
+/// assert_eq!(get_wuxing_relation(0, 2), WuXingRelation::Overcomes);
+/// ```
+pub fn get_wuxing_relation(
+    a: usize,
+    b: usize,
+) -> WuXingRelation {
+    match (b + 5 - a % 5) % 5 {
+        0 => WuXingRelation::Same,
+        1 => WuXingRelation::Generates,
+        2 => WuXingRelation::Overcomes,
+        3 => WuXingRelation::OvercomeBy,
+        _ => WuXingRelation::GeneratedBy,
+    }
+}
@@ -0,0 +1,398 @@
+//! 雑節 (Zassetsu) are the seasonal days used in
+//! Japanese calendars in addition to 二十四节气
+//! (Er-Shi-Si Jie-Qi). While 二十四节气 (Er-Shi-Si
+//! Jie-Qi) came from China, 雑節 (Zassetsu) were
+//! introduced to describe the seasonal changes which
+//! are specific to Japan (mostly for farming).
+//! Some are defined by the longitude of the sun
+//! (just like 二十四节气 (Er-Shi-Si Jie-Qi)), and
+//! some are defined by counting days from a certain
+//! solar term.
+//!
+//! [0] 節分 (Setsubun)
+//! [1] 春彼岸 (Spring Higan)
+//! [2] 春社日 (Spring Shanichi)
+//! [3] 八十八夜 (Hachijuhachiya)
+//! [4] 入梅 (Nyubai)
+//! [5] 半夏生 (Hangesho)
+//! [6] 冬土用 (Winter Doyo)
+//! [7] 春土用 (Spring Doyo)
+//! [8] 夏土用 (Summer Doyo)
+//! [9] 秋土用 (Autumn Doyo)
+//! [10] 二百十日 (Nihyakutoka)
+//! [11] 二百二十日 (Nihyakuhatsuka)
+//! [12] 秋彼岸 (Autumn Higan)
+//! [13] 秋社日 (Autumn Shanichi)
+
+use chrono::naive::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sowngwala::time::add_date;
+
+use crate::ephemeris::{
+    SunLongitudeProvider, DEFAULT_PROVIDER,
+};
+use crate::ganzhi::get_day_ganzhi_from_date;
+use crate::language::{
+    Language, LanguageData, LanguageTrait,
+    NameDataTrait,
+};
+use crate::solar_terms::{
+    get_date_of_sun_longitude_with, get_lichun_with,
+};
+use crate::utils::get_json;
+
+/// Kinds of 雑節 (Zassetsu). The order is the same
+/// as that of `ZASSETSU`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum ZassetsuKind {
+    Setsubun,       // 節分
+    SpringHigan,    // 春彼岸
+    SpringShanichi, // 春社日
+    Hachijuhachiya, // 八十八夜
+    Nyubai,         // 入梅
+    Hangesho,       // 半夏生
+    WinterDoyo,     // 冬土用
+    SpringDoyo,     // 春土用
+    SummerDoyo,     // 夏土用
+    AutumnDoyo,     // 秋土用
+    Nihyakutoka,    // 二百十日
+    Nihyakuhatsuka, // 二百二十日
+    AutumnHigan,    // 秋彼岸
+    AutumnShanichi, // 秋社日
+}
+
+/// A struct representing 雑節 (Zassetsu).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Zassetsu {
+    pub id: u8,
+    pub name: Language,
+}
+
+/// A temporary struct for loading JSON data when
+/// defining a static const `ZASSETSU`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZassetsuRawData {
+    pub id: u8,
+    pub name: LanguageData,
+}
+
+/// A struct for 雑節 (Zassetsu) of the specific year.
+/// For those lasting only for a day, `start` and `end`
+/// are the same.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZassetsuEvent {
+    pub kind: ZassetsuKind,
+    pub name: Language,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl LanguageTrait for Zassetsu {
+    fn name(&self) -> Box<Language> {
+        Box::new(self.name.clone())
+    }
+}
+
+impl NameDataTrait for ZassetsuRawData {
+    fn name(&self) -> Box<LanguageData> {
+        Box::new(self.name.clone())
+    }
+}
+
+impl LanguageTrait for ZassetsuEvent {
+    fn name(&self) -> Box<Language> {
+        Box::new(self.name.clone())
+    }
+}
+
+lazy_static! {
+    /// A static vector with 14 items, each represents
+    /// 雑節 (Zassetsu).
+    ///
+    /// For attributes details stored in the vector is
+    /// found in JSON file:
+    /// `src/json/zassetsu.json`
+    pub static ref ZASSETSU: Vec<Zassetsu> = {
+        let json = &include_str!("../json/zassetsu.json");
+        let data: Vec<ZassetsuRawData> =
+            get_json::<ZassetsuRawData>(json);
+        data.iter()
+            .map(|item| Zassetsu {
+                id: item.id,
+                name: item.language_from_data(),
+            })
+            .collect()
+    };
+}
+
+impl ZassetsuEvent {
+    fn new(
+        kind: ZassetsuKind,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Self {
+        ZassetsuEvent {
+            kind,
+            name: ZASSETSU[kind as usize]
+                .name
+                .clone(),
+            start,
+            end,
+        }
+    }
+
+    fn single(
+        kind: ZassetsuKind,
+        date: NaiveDate,
+    ) -> Self {
+        ZassetsuEvent::new(kind, date, date)
+    }
+}
+
+/// 彼岸 (Higan) lasts for 7 days having 春分
+/// (Chun-Fen) or 秋分 (Qiu-Fen) in the middle.
+fn higan(
+    provider: &dyn SunLongitudeProvider,
+    kind: ZassetsuKind,
+    year: i32,
+    angle: f64,
+) -> ZassetsuEvent {
+    let middle = get_date_of_sun_longitude_with(
+        provider, year, angle,
+    );
+    ZassetsuEvent::new(
+        kind,
+        add_date(middle, -3),
+        add_date(middle, 3),
+    )
+}
+
+/// 社日 (Shanichi) is the 戊 (Wu) day closest to 春分
+/// (Chun-Fen) or 秋分 (Qiu-Fen). When 春分 (Chun-Fen)
+/// or 秋分 (Qiu-Fen) falls exactly in between two 戊
+/// (Wu) days, the earlier one is taken.
+fn shanichi(
+    provider: &dyn SunLongitudeProvider,
+    kind: ZassetsuKind,
+    year: i32,
+    angle: f64,
+) -> ZassetsuEvent {
+    let middle = get_date_of_sun_longitude_with(
+        provider, year, angle,
+    );
+    let stem =
+        get_day_ganzhi_from_date(middle).stem.num;
+
+    // 戊 (Wu) is the 5th stem.
+    let diff: i64 = (5 - stem as i64).rem_euclid(10);
+    let date = if diff < 5 {
+        add_date(middle, diff)
+    } else {
+        add_date(middle, diff - 10)
+    };
+    ZassetsuEvent::single(kind, date)
+}
+
+/// 土用 (Doyo) begins when the sun reaches the given
+/// longitude, and ends on the day before the next
+/// 立春 (Li-Chun), 立夏 (Li-Xia), 立秋 (Li-Qiu), or
+/// 立冬 (Li-Dong), which is 18 degrees later.
+fn doyo(
+    provider: &dyn SunLongitudeProvider,
+    kind: ZassetsuKind,
+    year: i32,
+    angle: f64,
+) -> ZassetsuEvent {
+    ZassetsuEvent::new(
+        kind,
+        get_date_of_sun_longitude_with(
+            provider, year, angle,
+        ),
+        add_date(
+            get_date_of_sun_longitude_with(
+                provider,
+                year,
+                (angle + 18.0) % 360.0,
+            ),
+            -1,
+        ),
+    )
+}
+
+/// Returns 雑節 (Zassetsu) for the given year, sorted
+/// by the starting date.
+///
+/// Example:
+/// ```rust
+/// use mikaboshi::zassetsu::{get_zassetsu, ZassetsuEvent};
+///
+/// let events: Vec<ZassetsuEvent> = get_zassetsu(2022);
+/// for event in events.iter() {
+///     println!("{} {}", event.start, event.name.ja.alphabet);
+/// }
+/// ```
+pub fn get_zassetsu(year: i32) -> Vec<ZassetsuEvent> {
+    get_zassetsu_with(&DEFAULT_PROVIDER, year)
+}
+
+/// The same as `get_zassetsu`, but calculates the
+/// position of the sun using the given provider.
+pub fn get_zassetsu_with(
+    provider: &dyn SunLongitudeProvider,
+    year: i32,
+) -> Vec<ZassetsuEvent> {
+    use ZassetsuKind::*;
+
+    // Some are counted from 立春 (Li-Chun) where
+    // 立春 (Li-Chun) itself is the first day.
+    let lichun: NaiveDate =
+        get_lichun_with(provider, year);
+
+    let mut events = vec![
+        ZassetsuEvent::single(
+            Setsubun,
+            add_date(lichun, -1),
+        ),
+        higan(provider, SpringHigan, year, 0.0),
+        shanichi(provider, SpringShanichi, year, 0.0),
+        ZassetsuEvent::single(
+            Hachijuhachiya,
+            add_date(lichun, 87),
+        ),
+        ZassetsuEvent::single(
+            Nyubai,
+            get_date_of_sun_longitude_with(
+                provider, year, 80.0,
+            ),
+        ),
+        ZassetsuEvent::single(
+            Hangesho,
+            get_date_of_sun_longitude_with(
+                provider, year, 100.0,
+            ),
+        ),
+        doyo(provider, WinterDoyo, year, 297.0),
+        doyo(provider, SpringDoyo, year, 27.0),
+        doyo(provider, SummerDoyo, year, 117.0),
+        doyo(provider, AutumnDoyo, year, 207.0),
+        ZassetsuEvent::single(
+            Nihyakutoka,
+            add_date(lichun, 209),
+        ),
+        ZassetsuEvent::single(
+            Nihyakuhatsuka,
+            add_date(lichun, 219),
+        ),
+        higan(provider, AutumnHigan, year, 180.0),
+        shanichi(
+            provider,
+            AutumnShanichi,
+            year,
+            180.0,
+        ),
+    ];
+
+    events.sort_by_key(|event| event.start);
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(
+        events: &[ZassetsuEvent],
+        kind: ZassetsuKind,
+    ) -> &ZassetsuEvent {
+        events
+            .iter()
+            .find(|e| e.kind == kind)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_constant_zassetsu() {
+        assert_eq!(ZASSETSU.len(), 14);
+        assert_eq!(
+            ZASSETSU[ZassetsuKind::Setsubun as usize]
+                .alphabet_ja(),
+            "節分"
+        );
+    }
+
+    #[test]
+    fn test_get_zassetsu() {
+        let events = get_zassetsu(2022);
+        assert_eq!(events.len(), 14);
+
+        let setsubun =
+            find(&events, ZassetsuKind::Setsubun);
+        assert_eq!(
+            setsubun.start,
+            NaiveDate::from_ymd(2022, 2, 3)
+        );
+
+        let higan =
+            find(&events, ZassetsuKind::SpringHigan);
+        assert_eq!(
+            higan.start,
+            NaiveDate::from_ymd(2022, 3, 18)
+        );
+        assert_eq!(
+            higan.end,
+            NaiveDate::from_ymd(2022, 3, 24)
+        );
+
+        let hachijuhachiya = find(
+            &events,
+            ZassetsuKind::Hachijuhachiya,
+        );
+        assert_eq!(
+            hachijuhachiya.start,
+            NaiveDate::from_ymd(2022, 5, 2)
+        );
+
+        let doyo =
+            find(&events, ZassetsuKind::SummerDoyo);
+        assert_eq!(
+            doyo.start,
+            NaiveDate::from_ymd(2022, 7, 20)
+        );
+        // 土用 (Doyo) lasts for 18 or 19 days.
+        assert!((17..=18).contains(
+            &(doyo.end - doyo.start).num_days()
+        ));
+
+        let nihyakutoka =
+            find(&events, ZassetsuKind::Nihyakutoka);
+        assert_eq!(
+            nihyakutoka.start,
+            NaiveDate::from_ymd(2022, 9, 1)
+        );
+    }
+
+    #[test]
+    fn test_shanichi_is_wu_day() {
+        let events = get_zassetsu(2022);
+        for kind in [
+            ZassetsuKind::SpringShanichi,
+            ZassetsuKind::AutumnShanichi,
+        ] {
+            let date = find(&events, kind).start;
+            assert_eq!(
+                get_day_ganzhi_from_date(date)
+                    .stem
+                    .num,
+                5
+            );
+        }
+    }
+}